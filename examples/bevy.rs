@@ -50,44 +50,26 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     let state_machine = StateMachine::new(
         PlayerAnimState::Red,
         HashMap::from([
-            (
-                PlayerAnimState::Red,
-                rsanim::State {
-                    duration: 0.5,
-                    repeat: false,
-                },
+            (PlayerAnimState::Red, rsanim::State::new(0.5, false)),
+            (PlayerAnimState::Green, rsanim::State::new(0.5, false)),
+            (PlayerAnimState::Blue, rsanim::State::new(0.5, false)),
+        ]),
+        vec![
+            Transition::new(
+                TransitionStartState::Node(PlayerAnimState::Red),
+                TransitionEndState::Node(PlayerAnimState::Green),
+                TransitionTrigger::End,
             ),
-            (
-                PlayerAnimState::Green,
-                rsanim::State {
-                    duration: 0.5,
-                    repeat: false,
-                },
+            Transition::new(
+                TransitionStartState::Node(PlayerAnimState::Green),
+                TransitionEndState::Node(PlayerAnimState::Blue),
+                TransitionTrigger::End,
             ),
-            (
-                PlayerAnimState::Blue,
-                rsanim::State {
-                    duration: 0.5,
-                    repeat: false,
-                },
+            Transition::new(
+                TransitionStartState::Node(PlayerAnimState::Blue),
+                TransitionEndState::Node(PlayerAnimState::Red),
+                TransitionTrigger::End,
             ),
-        ]),
-        vec![
-            Transition {
-                start_state: TransitionStartState::Node(PlayerAnimState::Red),
-                end_state: TransitionEndState::Node(PlayerAnimState::Green),
-                trigger: TransitionTrigger::End,
-            },
-            Transition {
-                start_state: TransitionStartState::Node(PlayerAnimState::Green),
-                end_state: TransitionEndState::Node(PlayerAnimState::Blue),
-                trigger: TransitionTrigger::End,
-            },
-            Transition {
-                start_state: TransitionStartState::Node(PlayerAnimState::Blue),
-                end_state: TransitionEndState::Node(PlayerAnimState::Red),
-                trigger: TransitionTrigger::End,
-            },
         ],
         PlayerAnimParams {},
     )
@@ -61,43 +61,34 @@ fn setup(mut commands: Commands) {
             anim: StateMachine::new(
                 PlayerAnimState::Red,
                 HashMap::from([
-                    (
-                        PlayerAnimState::Red,
-                        rsanim::State {
-                            duration: 1.0,
-                            repeat: false,
-                        },
-                    ),
-                    (
-                        PlayerAnimState::Green,
-                        rsanim::State {
-                            duration: 1.0,
-                            repeat: false,
-                        },
-                    ),
-                    (
-                        PlayerAnimState::Blue,
-                        rsanim::State {
-                            duration: 1.0,
-                            repeat: false,
-                        },
-                    ),
+                    (PlayerAnimState::Red, rsanim::State::new(1.0, false)),
+                    (PlayerAnimState::Green, rsanim::State::new(1.0, false)),
+                    (PlayerAnimState::Blue, rsanim::State::new(1.0, false)),
                 ]),
                 vec![
                     Transition {
-                        start_state: TransitionStartState::Node(PlayerAnimState::Red),
-                        end_state: TransitionEndState::Node(PlayerAnimState::Green),
-                        trigger: TransitionTrigger::End,
+                        blend_duration: Some(0.25),
+                        ..Transition::new(
+                            TransitionStartState::Node(PlayerAnimState::Red),
+                            TransitionEndState::Node(PlayerAnimState::Green),
+                            TransitionTrigger::End,
+                        )
                     },
                     Transition {
-                        start_state: TransitionStartState::Node(PlayerAnimState::Green),
-                        end_state: TransitionEndState::Node(PlayerAnimState::Blue),
-                        trigger: TransitionTrigger::End,
+                        blend_duration: Some(0.25),
+                        ..Transition::new(
+                            TransitionStartState::Node(PlayerAnimState::Green),
+                            TransitionEndState::Node(PlayerAnimState::Blue),
+                            TransitionTrigger::End,
+                        )
                     },
                     Transition {
-                        start_state: TransitionStartState::Node(PlayerAnimState::Blue),
-                        end_state: TransitionEndState::Node(PlayerAnimState::Red),
-                        trigger: TransitionTrigger::End,
+                        blend_duration: Some(0.25),
+                        ..Transition::new(
+                            TransitionStartState::Node(PlayerAnimState::Blue),
+                            TransitionEndState::Node(PlayerAnimState::Red),
+                            TransitionTrigger::End,
+                        )
                     },
                 ],
                 PlayerAnimParams {},
@@ -144,17 +135,26 @@ pub fn player_render(mut query: Query<(&Player, &mut Sprite)>) {
         return;
     };
 
-    let color_intensity = 1.0 - player.anim.state().progress() / 2.0;
-
-    match player.anim.state().key {
-        PlayerAnimState::Red => {
-            sprite.color = Color::srgb(color_intensity, 0.0, 0.0);
-        }
-        PlayerAnimState::Green => {
-            sprite.color = Color::srgb(0.0, color_intensity, 0.0);
-        }
-        PlayerAnimState::Blue => {
-            sprite.color = Color::srgb(0.0, 0.0, color_intensity);
-        }
+    let [mut red, mut green, mut blue] = state_color(player.anim.state());
+
+    // crossfade from the previous state's color
+    if let Some(blend) = player.anim.blend() {
+        let weight = blend.weight();
+        let [from_red, from_green, from_blue] = state_color(&blend.from);
+        red = red * (1.0 - weight) + from_red * weight;
+        green = green * (1.0 - weight) + from_green * weight;
+        blue = blue * (1.0 - weight) + from_blue * weight;
+    }
+
+    sprite.color = Color::srgb(red, green, blue);
+}
+
+fn state_color(state: &CurrentState<PlayerAnimState>) -> [f32; 3] {
+    let color_intensity = 1.0 - state.progress() / 2.0;
+
+    match state.key {
+        PlayerAnimState::Red => [color_intensity, 0.0, 0.0],
+        PlayerAnimState::Green => [0.0, color_intensity, 0.0],
+        PlayerAnimState::Blue => [0.0, 0.0, color_intensity],
     }
 }
@@ -0,0 +1,196 @@
+//! An importer for the JSON data Aseprite exports with sprite sheets, see
+//! [`AsepriteSheet::from_json`].
+
+use std::fmt::Formatter;
+
+use serde::Deserialize;
+use serde::de::{MapAccess, SeqAccess, Visitor};
+
+use crate::prelude::*;
+
+/// The states and frames of the frame tags of an Aseprite sprite sheet, keyed by tag name
+#[derive(Clone, Debug)]
+pub struct AsepriteSheet {
+    /// The states
+    pub states: HashMap<String, State>,
+    /// The frames of the states, as indices into the sprite sheet
+    pub state_frames: HashMap<String, Vec<Frame<usize>>>,
+}
+
+impl AsepriteSheet {
+    /// Imports the JSON data exported with `--data`, in either the hash or the array format.
+    ///
+    /// Each frame tag becomes a state that plays its frames for their durations. Reverse tags
+    /// play backwards and a repeat count limits the loops, a ping-pong loop being a pass forwards
+    /// and back. Ping-pong tags list their frames in the order Aseprite plays them, showing the
+    /// frames they bounce off once.
+    pub fn from_json(json: &str) -> Result<Self, AsepriteError> {
+        let data: AsepriteData =
+            serde_json::from_str(json).map_err(|x| AsepriteError::Json(x.to_string()))?;
+
+        let mut sheet = Self {
+            states: HashMap::new(),
+            state_frames: HashMap::new(),
+        };
+        for tag in data.meta.frame_tags {
+            if sheet.states.contains_key(&tag.name) {
+                return Err(AsepriteError::DuplicateTag(tag.name));
+            }
+            if tag.from > tag.to || tag.to >= data.frames.0.len() {
+                return Err(AsepriteError::InvalidTagRange(tag.name));
+            }
+
+            let repeat = match &tag.repeat {
+                None => None,
+                Some(repeat) => match repeat.parse::<u32>() {
+                    Ok(repeat) if repeat > 0 => Some(repeat),
+                    _ => return Err(AsepriteError::InvalidTagRepeat(tag.name, repeat.clone())),
+                },
+            };
+            let forward = (tag.from..=tag.to).collect::<Vec<_>>();
+            let backward = forward.iter().copied().rev().collect::<Vec<_>>();
+            let (indices, playback, speed) = match (tag.direction.as_str(), repeat) {
+                ("forward", None) => (forward, Playback::Loop, 1.0),
+                ("forward", Some(1)) => (forward, Playback::Once, 1.0),
+                ("forward", Some(repeat)) => (forward, Playback::LoopCount(repeat), 1.0),
+                ("reverse", None) => (forward, Playback::Loop, -1.0),
+                ("reverse", Some(1)) => (forward, Playback::Once, -1.0),
+                ("reverse", Some(repeat)) => (forward, Playback::LoopCount(repeat), -1.0),
+                ("pingpong", repeat) => ping_pong(&forward, &backward, repeat),
+                ("pingpong_reverse", repeat) => ping_pong(&backward, &forward, repeat),
+                _ => return Err(AsepriteError::UnknownTagDirection(tag.name, tag.direction)),
+            };
+
+            // Aseprite frame durations are in milliseconds
+            let timed_frames = indices
+                .into_iter()
+                .map(|index| TimedFrame {
+                    duration: data.frames.0[index].duration as f32 / 1000.0,
+                    value: index,
+                })
+                .collect::<Vec<_>>();
+            let duration = TimedFrame::total_duration(&timed_frames);
+            if duration <= 0.0 {
+                return Err(AsepriteError::InvalidTagDuration(tag.name));
+            }
+
+            sheet.states.insert(
+                tag.name.clone(),
+                State {
+                    speed,
+                    ..State::with_playback(duration, playback)
+                },
+            );
+            sheet
+                .state_frames
+                .insert(tag.name, Frame::from_timed(timed_frames));
+        }
+        Ok(sheet)
+    }
+}
+
+/// Lists the frames of a ping-pong tag in play order, without repeating the frames it bounces off.
+///
+/// A counted ping-pong is unrolled, so that it stops back on its first frame.
+fn ping_pong(there: &[usize], back: &[usize], repeat: Option<u32>) -> (Vec<usize>, Playback, f32) {
+    let bounce = back
+        .get(1..back.len().saturating_sub(1))
+        .unwrap_or_default();
+    let mut indices = [there, bounce].concat();
+    match repeat {
+        None => (indices, Playback::Loop, 1.0),
+        Some(repeat) => {
+            indices = indices.repeat(repeat as usize);
+            indices.push(there[0]);
+            (indices, Playback::Once, 1.0)
+        }
+    }
+}
+
+/// An Aseprite import error
+#[derive(Clone, PartialEq, Debug)]
+pub enum AsepriteError {
+    /// The data is not valid Aseprite JSON
+    Json(String),
+    /// More than one frame tag has the name
+    DuplicateTag(String),
+    /// The frames of the frame tag are out of order or out of the sprite sheet
+    InvalidTagRange(String),
+    /// The direction of the frame tag is not supported
+    UnknownTagDirection(String, String),
+    /// The repeat count of the frame tag is not a positive number
+    InvalidTagRepeat(String, String),
+    /// The frames of the frame tag have no duration
+    InvalidTagDuration(String),
+}
+
+#[derive(Deserialize)]
+struct AsepriteData {
+    frames: AsepriteFrames,
+    meta: AsepriteMeta,
+}
+
+#[derive(Deserialize)]
+struct AsepriteFrame {
+    duration: u32,
+}
+
+/// The frames in the order they appear in the data, which JSON objects do not keep otherwise
+struct AsepriteFrames(Vec<AsepriteFrame>);
+
+impl<'de> Deserialize<'de> for AsepriteFrames {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(AsepriteFramesVisitor)
+    }
+}
+
+struct AsepriteFramesVisitor;
+
+impl<'de> Visitor<'de> for AsepriteFramesVisitor {
+    type Value = AsepriteFrames;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "an array or a map of frames")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut frames = Vec::new();
+        while let Some(frame) = seq.next_element()? {
+            frames.push(frame);
+        }
+        Ok(AsepriteFrames(frames))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut frames = Vec::new();
+        while let Some((_, frame)) = map.next_entry::<String, _>()? {
+            frames.push(frame);
+        }
+        Ok(AsepriteFrames(frames))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AsepriteMeta {
+    #[serde(default)]
+    frame_tags: Vec<AsepriteTag>,
+}
+
+#[derive(Deserialize)]
+struct AsepriteTag {
+    name: String,
+    from: usize,
+    to: usize,
+    direction: String,
+    repeat: Option<String>,
+}
@@ -0,0 +1,326 @@
+//! Definitions of state machines and animators that can be loaded from files, such as RON or
+//! JSON, with the conditions of their transitions looked up by name in a [`ConditionRegistry`].
+
+use std::hash::Hash;
+
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+use crate::{ContextFn, ParameterFn};
+
+/// A [`StateMachine`] definition
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K: Serialize + Eq + Hash",
+    deserialize = "K: Deserialize<'de> + Eq + Hash"
+))]
+pub struct StateMachineDefinition<K> {
+    /// The starting state
+    pub starting_state: K,
+    /// The states
+    pub states: HashMap<K, State>,
+    /// The nested state machines, keyed by their parent state
+    #[serde(default)]
+    pub sub_machines: HashMap<K, SubStateMachineDefinition<K>>,
+    /// The transitions
+    #[serde(default)]
+    pub transitions: Vec<TransitionDefinition<K>>,
+}
+
+impl<K> StateMachineDefinition<K>
+where
+    K: Clone + Eq + PartialEq + Hash,
+{
+    /// Builds the [`StateMachine`], looking up its conditions in the registry
+    pub fn build<V>(
+        self,
+        registry: &ConditionRegistry<K, V>,
+        parameters: V,
+    ) -> Result<StateMachine<K, V>, DefinitionError<K>> {
+        StateMachine::new_nested(
+            self.starting_state,
+            self.states,
+            build_sub_machines(self.sub_machines, registry)?,
+            build_transitions(self.transitions, registry)?,
+            parameters,
+        )
+        .map_err(DefinitionError::StateMachine)
+    }
+}
+
+/// A [`SubStateMachine`] definition
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K: Serialize + Eq + Hash",
+    deserialize = "K: Deserialize<'de> + Eq + Hash"
+))]
+pub struct SubStateMachineDefinition<K> {
+    /// The state entered when the parent state is entered
+    pub entry_state: K,
+    /// The states
+    pub states: HashMap<K, State>,
+    /// The nested state machines, keyed by their parent state
+    #[serde(default)]
+    pub sub_machines: HashMap<K, SubStateMachineDefinition<K>>,
+    /// The local transitions
+    #[serde(default)]
+    pub transitions: Vec<TransitionDefinition<K>>,
+}
+
+fn build_sub_machines<K, V>(
+    sub_machines: HashMap<K, SubStateMachineDefinition<K>>,
+    registry: &ConditionRegistry<K, V>,
+) -> Result<HashMap<K, SubStateMachine<K, V>>, DefinitionError<K>>
+where
+    K: Clone + Eq + PartialEq + Hash,
+{
+    sub_machines
+        .into_iter()
+        .map(|(key, x)| {
+            let sub_machine = SubStateMachine {
+                entry_state: x.entry_state,
+                states: x.states,
+                sub_machines: build_sub_machines(x.sub_machines, registry)?,
+                transitions: build_transitions(x.transitions, registry)?,
+            };
+            Ok((key, sub_machine))
+        })
+        .collect()
+}
+
+fn build_transitions<K, V>(
+    transitions: Vec<TransitionDefinition<K>>,
+    registry: &ConditionRegistry<K, V>,
+) -> Result<Vec<Transition<K, V>>, DefinitionError<K>> {
+    transitions
+        .into_iter()
+        .map(|x| {
+            Ok(Transition {
+                exit_time: x.exit_time,
+                blend_duration: x.blend_duration,
+                self_transition: x.self_transition,
+                priority: x.priority,
+                ..Transition::new(x.start_state, x.end_state, x.trigger.build(registry)?)
+            })
+        })
+        .collect()
+}
+
+/// A [`Transition`] definition
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TransitionDefinition<K> {
+    /// The start state
+    pub start_state: TransitionStartState<K>,
+    /// The end state
+    pub end_state: TransitionEndState<K>,
+    /// The trigger
+    pub trigger: TriggerDefinition,
+    /// See [`Transition::exit_time`]
+    #[serde(default)]
+    pub exit_time: Option<f32>,
+    /// See [`Transition::blend_duration`]
+    #[serde(default)]
+    pub blend_duration: Option<f32>,
+    /// See [`Transition::self_transition`]
+    #[serde(default)]
+    pub self_transition: bool,
+    /// See [`Transition::priority`]
+    #[serde(default)]
+    pub priority: i32,
+}
+
+/// A [`TransitionTrigger`] definition
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum TriggerDefinition {
+    /// A [`TransitionTrigger::Condition`] or [`TransitionTrigger::Context`], by the name it is
+    /// registered with
+    Condition(String),
+    /// A [`TransitionTrigger::Compare`], see [`ConditionRegistry::with_parameters`]
+    Compare {
+        /// The name of the parameter
+        parameter: String,
+        /// How the parameter is compared
+        operator: ComparisonOperator,
+        /// The value the parameter is compared against
+        value: ParameterValue,
+    },
+    /// A [`TransitionTrigger::Event`]
+    Event(String),
+    /// A [`TransitionTrigger::End`]
+    End,
+    /// A [`TransitionTrigger::All`]
+    All(Vec<TriggerDefinition>),
+    /// A [`TransitionTrigger::Any`]
+    Any(Vec<TriggerDefinition>),
+    /// A [`TransitionTrigger::Not`]
+    Not(Box<TriggerDefinition>),
+    /// A [`TransitionTrigger::After`]
+    After(f32),
+    /// A [`TransitionTrigger::Loops`]
+    Loops(u32),
+    /// A [`TransitionTrigger::FrameReached`]
+    FrameReached(usize),
+}
+
+impl TriggerDefinition {
+    fn build<K, V>(
+        self,
+        registry: &ConditionRegistry<K, V>,
+    ) -> Result<TransitionTrigger<K, V>, DefinitionError<K>> {
+        let build_all = |triggers: Vec<TriggerDefinition>| {
+            triggers
+                .into_iter()
+                .map(|x| x.build(registry))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(match self {
+            TriggerDefinition::Condition(name) => match registry.conditions.get(&name) {
+                Some(RegisteredCondition::Condition(condition)) => {
+                    TransitionTrigger::Condition(condition.clone())
+                }
+                Some(RegisteredCondition::Context(condition)) => {
+                    TransitionTrigger::Context(condition.clone())
+                }
+                None => return Err(DefinitionError::UnknownCondition(name)),
+            },
+            TriggerDefinition::Compare {
+                parameter,
+                operator,
+                value,
+            } => {
+                let Some((get, reset_trigger)) = registry.parameters else {
+                    return Err(DefinitionError::UnsupportedCompare(parameter));
+                };
+                TransitionTrigger::Compare(Comparison {
+                    parameter,
+                    operator,
+                    value,
+                    get,
+                    reset_trigger,
+                })
+            }
+            TriggerDefinition::Event(event) => TransitionTrigger::Event(event),
+            TriggerDefinition::End => TransitionTrigger::End,
+            TriggerDefinition::All(triggers) => TransitionTrigger::All(build_all(triggers)?),
+            TriggerDefinition::Any(triggers) => TransitionTrigger::Any(build_all(triggers)?),
+            TriggerDefinition::Not(trigger) => {
+                TransitionTrigger::Not(Box::new(trigger.build(registry)?))
+            }
+            TriggerDefinition::After(seconds) => TransitionTrigger::After(seconds),
+            TriggerDefinition::Loops(loops) => TransitionTrigger::Loops(loops),
+            TriggerDefinition::FrameReached(index) => TransitionTrigger::FrameReached(index),
+        })
+    }
+}
+
+/// An [`Animator`] definition
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K: Serialize + Eq + Hash, F: Serialize",
+    deserialize = "K: Deserialize<'de> + Eq + Hash, F: Deserialize<'de>"
+))]
+pub struct AnimatorDefinition<K, F> {
+    /// The state machine
+    pub state_machine: StateMachineDefinition<K>,
+    /// The frames of the states
+    pub state_frames: HashMap<K, Vec<Frame<F>>>,
+}
+
+impl<K, F> AnimatorDefinition<K, F>
+where
+    K: Clone + Eq + PartialEq + Hash,
+{
+    /// Builds the [`Animator`], looking up the conditions of its state machine in the registry
+    pub fn build<V>(
+        self,
+        registry: &ConditionRegistry<K, V>,
+        parameters: V,
+    ) -> Result<Animator<K, V, F>, DefinitionError<K>> {
+        let state_machine = self.state_machine.build(registry, parameters)?;
+        Animator::new(state_machine, self.state_frames).map_err(DefinitionError::Animator)
+    }
+}
+
+/// The conditions that definitions refer to by name
+pub struct ConditionRegistry<K, V> {
+    conditions: HashMap<String, RegisteredCondition<K, V>>,
+    parameters: Option<ParameterLookup<V>>,
+}
+
+/// How [`Parameters`] are read and reset, see [`Comparison`]
+type ParameterLookup<V> = (fn(&V, &str) -> Option<ParameterValue>, fn(&mut V, &str));
+
+enum RegisteredCondition<K, V> {
+    Condition(ParameterFn<V, bool>),
+    Context(ContextFn<K, V>),
+}
+
+impl<K, V> ConditionRegistry<K, V> {
+    /// Creates a new, empty [`ConditionRegistry`]
+    pub fn new() -> Self {
+        Self {
+            conditions: HashMap::new(),
+            parameters: None,
+        }
+    }
+
+    /// Registers a [`TransitionTrigger::Condition`] under a name
+    pub fn with_condition(
+        mut self,
+        name: impl Into<String>,
+        condition: impl Fn(&V) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.conditions.insert(
+            name.into(),
+            RegisteredCondition::Condition(Arc::new(condition)),
+        );
+        self
+    }
+
+    /// Registers a [`TransitionTrigger::Context`] under a name
+    pub fn with_context(
+        mut self,
+        name: impl Into<String>,
+        condition: impl Fn(&ConditionContext<K, V>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.conditions.insert(
+            name.into(),
+            RegisteredCondition::Context(Arc::new(condition)),
+        );
+        self
+    }
+}
+
+impl<K, V> ConditionRegistry<K, V>
+where
+    V: Parameters,
+{
+    /// Creates a new, empty [`ConditionRegistry`] that also resolves
+    /// [`TriggerDefinition::Compare`]
+    pub fn with_parameters() -> Self {
+        Self {
+            conditions: HashMap::new(),
+            parameters: Some((V::parameter, V::reset_trigger)),
+        }
+    }
+}
+
+impl<K, V> Default for ConditionRegistry<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A definition error
+#[derive(Clone, PartialEq, Debug)]
+pub enum DefinitionError<K> {
+    /// No condition is registered under the name
+    UnknownCondition(String),
+    /// A parameter is compared, but the registry was not created with
+    /// [`ConditionRegistry::with_parameters`]
+    UnsupportedCompare(String),
+    /// The state machine is invalid
+    StateMachine(StateMachineError<K>),
+    /// The animator is invalid
+    Animator(AnimatorError<K>),
+}
@@ -22,32 +22,20 @@
 //! let mut state_machine = StateMachine::new(
 //!     Animation::Idle,
 //!     HashMap::from([
-//!         (
-//!             Animation::Idle,
-//!             State {
-//!                 duration: 0.5,
-//!                 repeat: true,
-//!             },
-//!         ),
-//!         (
-//!             Animation::Run,
-//!             State {
-//!                 duration: 1.0,
-//!                 repeat: true,
-//!             },
-//!         ),
+//!         (Animation::Idle, State::new(0.5, true)),
+//!         (Animation::Run, State::new(1.0, true)),
 //!     ]),
 //!     vec![
-//!         Transition {
-//!             start_state: TransitionStartState::Node(Animation::Idle),
-//!             end_state: TransitionEndState::Node(Animation::Run),
-//!             trigger: TransitionTrigger::Condition(Box::new(|x: &Params| x.speed > 0.0)),
-//!         },
-//!         Transition {
-//!             start_state: TransitionStartState::Node(Animation::Run),
-//!             end_state: TransitionEndState::Node(Animation::Idle),
-//!             trigger: TransitionTrigger::Condition(Box::new(|x: &Params| x.speed <= 0.0)),
-//!         },
+//!         Transition::new(
+//!             TransitionStartState::Node(Animation::Idle),
+//!             TransitionEndState::Node(Animation::Run),
+//!             TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed > 0.0)),
+//!         ),
+//!         Transition::new(
+//!             TransitionStartState::Node(Animation::Run),
+//!             TransitionEndState::Node(Animation::Idle),
+//!             TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed <= 0.0)),
+//!         ),
 //!     ],
 //!     Params { speed: 0.0 },
 //! )
@@ -114,32 +102,20 @@
 //! # let mut state_machine = StateMachine::new(
 //! #     Animation::Idle,
 //! #     HashMap::from([
-//! #         (
-//! #             Animation::Idle,
-//! #             State {
-//! #                 duration: 0.5,
-//! #                 repeat: true,
-//! #             },
-//! #         ),
-//! #         (
-//! #             Animation::Run,
-//! #             State {
-//! #                 duration: 1.0,
-//! #                 repeat: true,
-//! #             },
-//! #         ),
+//! #         (Animation::Idle, State::new(0.5, true)),
+//! #         (Animation::Run, State::new(1.0, true)),
 //! #     ]),
 //! #     vec![
-//! #         Transition {
-//! #             start_state: TransitionStartState::Node(Animation::Idle),
-//! #             end_state: TransitionEndState::Node(Animation::Run),
-//! #             trigger: TransitionTrigger::Condition(Box::new(|x: &Params| x.speed > 0.0)),
-//! #         },
-//! #         Transition {
-//! #             start_state: TransitionStartState::Node(Animation::Run),
-//! #             end_state: TransitionEndState::Node(Animation::Idle),
-//! #             trigger: TransitionTrigger::Condition(Box::new(|x: &Params| x.speed <= 0.0)),
-//! #         },
+//! #         Transition::new(
+//! #             TransitionStartState::Node(Animation::Idle),
+//! #             TransitionEndState::Node(Animation::Run),
+//! #             TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed > 0.0)),
+//! #         ),
+//! #         Transition::new(
+//! #             TransitionStartState::Node(Animation::Run),
+//! #             TransitionEndState::Node(Animation::Idle),
+//! #             TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed <= 0.0)),
+//! #         ),
 //! #     ],
 //! #     Params { speed: 0.0 },
 //! # )
@@ -208,32 +184,20 @@
 //! # let mut state_machine = StateMachine::new(
 //! #     Animation::Idle,
 //! #     HashMap::from([
-//! #         (
-//! #             Animation::Idle,
-//! #             State {
-//! #                 duration: 0.5,
-//! #                 repeat: true,
-//! #             },
-//! #         ),
-//! #         (
-//! #             Animation::Run,
-//! #             State {
-//! #                 duration: 1.0,
-//! #                 repeat: true,
-//! #             },
-//! #         ),
+//! #         (Animation::Idle, State::new(0.5, true)),
+//! #         (Animation::Run, State::new(1.0, true)),
 //! #     ]),
 //! #     vec![
-//! #         Transition {
-//! #             start_state: TransitionStartState::Node(Animation::Idle),
-//! #             end_state: TransitionEndState::Node(Animation::Run),
-//! #             trigger: TransitionTrigger::Condition(Box::new(|x: &Params| x.speed > 0.0)),
-//! #         },
-//! #         Transition {
-//! #             start_state: TransitionStartState::Node(Animation::Run),
-//! #             end_state: TransitionEndState::Node(Animation::Idle),
-//! #             trigger: TransitionTrigger::Condition(Box::new(|x: &Params| x.speed <= 0.0)),
-//! #         },
+//! #         Transition::new(
+//! #             TransitionStartState::Node(Animation::Idle),
+//! #             TransitionEndState::Node(Animation::Run),
+//! #             TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed > 0.0)),
+//! #         ),
+//! #         Transition::new(
+//! #             TransitionStartState::Node(Animation::Run),
+//! #             TransitionEndState::Node(Animation::Idle),
+//! #             TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed <= 0.0)),
+//! #         ),
 //! #     ],
 //! #     Params { speed: 0.0 },
 //! # )
@@ -284,18 +248,45 @@
 //! });
 //! ```
 
+use std::cmp::{Ordering, Reverse};
 use std::fmt::{Debug, Formatter};
 use std::hash::Hash;
 
 use crate::prelude::*;
 
+#[cfg(feature = "aseprite")]
+mod aseprite;
+#[cfg(feature = "serde")]
+mod definition;
+
+#[cfg(feature = "aseprite")]
+pub use aseprite::{AsepriteError, AsepriteSheet};
+
+#[cfg(feature = "serde")]
+pub use definition::{
+    AnimatorDefinition, ConditionRegistry, DefinitionError, StateMachineDefinition,
+    SubStateMachineDefinition, TransitionDefinition, TriggerDefinition,
+};
+
 #[doc(hidden)]
 pub mod prelude {
-    pub use bevy_platform::collections::{HashMap, HashSet};
     pub use super::{
-        Animator, AnimatorError, CurrentState, Frame, State, StateMachine,
-        StateMachineError, Transition, TransitionEndState, TransitionStartState, TransitionTrigger,
+        Animator, AnimatorError, AnimatorEvent, Blend, BlendClip, BlendParameter, BlendSpace,
+        Comparison, ComparisonOperator, ConditionContext, CurrentState, EvaluationPolicy, Frame,
+        FrameEvent, LayerBlending, ParameterMap, ParameterValue, Parameters, Playback,
+        PlaybackDirection, State, StateMachine, StateMachineError, StateMachineEvent,
+        SubStateMachine, TimedFrame, Transition, TransitionEndState, TransitionStartState,
+        TransitionTrigger,
     };
+    #[cfg(feature = "serde")]
+    pub use super::{
+        AnimatorDefinition, ConditionRegistry, DefinitionError, StateMachineDefinition,
+        SubStateMachineDefinition, TransitionDefinition, TriggerDefinition,
+    };
+    #[cfg(feature = "aseprite")]
+    pub use super::{AsepriteError, AsepriteSheet};
+    pub use bevy_platform::collections::{HashMap, HashSet};
+    pub use std::sync::Arc;
 }
 
 /// The animator.
@@ -319,32 +310,20 @@ pub mod prelude {
 /// let mut state_machine = StateMachine::new(
 ///     Animation::Idle,
 ///     HashMap::from([
-///         (
-///             Animation::Idle,
-///             State {
-///                 duration: 0.5,
-///                 repeat: true,
-///             },
-///         ),
-///         (
-///             Animation::Run,
-///             State {
-///                 duration: 1.0,
-///                 repeat: true,
-///             },
-///         ),
+///         (Animation::Idle, State::new(0.5, true)),
+///         (Animation::Run, State::new(1.0, true)),
 ///     ]),
 ///     vec![
-///         Transition {
-///             start_state: TransitionStartState::Node(Animation::Idle),
-///             end_state: TransitionEndState::Node(Animation::Run),
-///             trigger: TransitionTrigger::Condition(Box::new(|x: &Params| x.speed > 0.0)),
-///         },
-///         Transition {
-///             start_state: TransitionStartState::Node(Animation::Run),
-///             end_state: TransitionEndState::Node(Animation::Idle),
-///             trigger: TransitionTrigger::Condition(Box::new(|x: &Params| x.speed <= 0.0)),
-///         },
+///         Transition::new(
+///             TransitionStartState::Node(Animation::Idle),
+///             TransitionEndState::Node(Animation::Run),
+///             TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed > 0.0)),
+///         ),
+///         Transition::new(
+///             TransitionStartState::Node(Animation::Run),
+///             TransitionEndState::Node(Animation::Idle),
+///             TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed <= 0.0)),
+///         ),
 ///     ],
 ///     Params { speed: 0.0 },
 /// )
@@ -398,9 +377,12 @@ pub mod prelude {
 /// animator.update(0.1);
 /// ```
 #[derive(Clone, Debug)]
-pub struct Animator<K, V, F> {
+pub struct Animator<K, V, F, E = ()> {
     state_machine: StateMachine<K, V>,
     state_frames: HashMap<K, Vec<Frame<F>>>,
+    state_events: HashMap<K, Vec<FrameEvent<E>>>,
+    blend_spaces: HashMap<K, BlendSpace<V, F>>,
+    layers: Vec<AnimatorLayer<K, V, F>>,
 }
 
 impl<K, V, F> Animator<K, V, F>
@@ -411,47 +393,317 @@ where
     pub fn new(
         state_machine: StateMachine<K, V>,
         state_frames: HashMap<K, Vec<Frame<F>>>,
+    ) -> Result<Self, AnimatorError<K>> {
+        Self::with_blend_spaces(state_machine, state_frames, HashMap::new())
+    }
+
+    /// Creates a new [`Animator`] from frames with their own durations.
+    ///
+    /// The total duration of each state's frames must match the state duration, see
+    /// [`TimedFrame::total_duration`].
+    pub fn new_timed(
+        state_machine: StateMachine<K, V>,
+        state_frames: HashMap<K, Vec<TimedFrame<F>>>,
+    ) -> Result<Self, AnimatorError<K>> {
+        for (key, frames) in &state_frames {
+            if let Some(frame) = frames.iter().find(|x| x.duration < 0.0) {
+                return Err(AnimatorError::InvalidStateFrameDuration(
+                    key.clone(),
+                    frame.duration,
+                ));
+            }
+
+            // Allow for rounding errors when summing up the frame durations
+            let duration = TimedFrame::total_duration(frames);
+            if let Some(state) = state_machine.states.get(key)
+                && (duration - state.duration).abs() > 1e-4 * state.duration.max(1.0)
+            {
+                return Err(AnimatorError::MismatchedStateFrameDuration(
+                    key.clone(),
+                    duration,
+                ));
+            }
+        }
+
+        let state_frames = state_frames
+            .into_iter()
+            .map(|(key, frames)| (key, Frame::from_timed(frames)))
+            .collect();
+        Self::new(state_machine, state_frames)
+    }
+
+    /// Creates a new [`Animator`] where some states pick their frames from a [`BlendSpace`]
+    /// instead of `state_frames`.
+    ///
+    /// The duration of a blend space state follows the picked clip.
+    pub fn with_blend_spaces(
+        mut state_machine: StateMachine<K, V>,
+        state_frames: HashMap<K, Vec<Frame<F>>>,
+        blend_spaces: HashMap<K, BlendSpace<V, F>>,
     ) -> Result<Self, AnimatorError<K>> {
         for state in state_machine.states.keys() {
-            match state_frames.get(state) {
-                Some(frames) => {
-                    if frames.is_empty() {
-                        return Err(AnimatorError::EmptyStateFrames(state.clone()));
+            match (state_frames.get(state), blend_spaces.get(state)) {
+                (_, Some(blend_space)) => {
+                    if blend_space.clips.is_empty() {
+                        return Err(AnimatorError::EmptyBlendSpace(state.clone()));
                     }
-
-                    // make sure frames are sorted by progress
-                    let mut last_progress = -1.0;
-                    for frame in frames {
-                        if frame.progress < last_progress {
-                            return Err(AnimatorError::UnsortedStateFrames(state.clone()));
-                        }
-                        if frame.progress < 0.0 || frame.progress > 1.0 {
-                            return Err(AnimatorError::InvalidStateFrameProgress(
+                    for clip in &blend_space.clips {
+                        if clip.duration <= 0.0 {
+                            return Err(AnimatorError::InvalidBlendClipDuration(
                                 state.clone(),
-                                frame.progress,
+                                clip.duration,
                             ));
                         }
-                        last_progress = frame.progress;
+                        validate_frames(state, &clip.frames)?;
                     }
                 }
-                None => return Err(AnimatorError::MissingStateFrames(state.clone())),
+                (Some(frames), None) => validate_frames(state, frames)?,
+                (None, None) => return Err(AnimatorError::MissingStateFrames(state.clone())),
             }
         }
 
-        Ok(Self {
+        state_machine.frame_progress = frame_progress(&state_frames);
+        let mut animator = Self {
             state_machine,
             state_frames,
+            state_events: HashMap::new(),
+            blend_spaces,
+            layers: Vec::new(),
+        };
+        animator.pick_blend_clips();
+        Ok(animator)
+    }
+
+    /// Attaches events to the states, which [`Animator::update`] returns when their progress is
+    /// crossed.
+    pub fn with_events<E>(
+        self,
+        state_events: HashMap<K, Vec<FrameEvent<E>>>,
+    ) -> Result<Animator<K, V, F, E>, AnimatorError<K>> {
+        for (state, events) in &state_events {
+            // make sure events are sorted by progress
+            let mut last_progress = -1.0;
+            for event in events {
+                if event.progress < last_progress {
+                    return Err(AnimatorError::UnsortedStateEvents(state.clone()));
+                }
+                if event.progress < 0.0 || event.progress > 1.0 {
+                    return Err(AnimatorError::InvalidStateEventProgress(
+                        state.clone(),
+                        event.progress,
+                    ));
+                }
+                last_progress = event.progress;
+            }
+        }
+
+        Ok(Animator {
+            state_machine: self.state_machine,
+            state_frames: self.state_frames,
+            state_events,
+            blend_spaces: self.blend_spaces,
+            layers: self.layers,
         })
     }
+}
+
+impl<K, V, F, E> Animator<K, V, F, E>
+where
+    K: Clone + Eq + PartialEq + Hash,
+{
+    /// Matches the durations of blend space states to their picked clips, keeping the progress
+    /// of the current state
+    fn pick_blend_clips(&mut self) {
+        for (key, blend_space) in &self.blend_spaces {
+            let Some(clip) = blend_space.clip(&self.state_machine.parameters) else {
+                continue;
+            };
+            if let Some(state) = self.state_machine.states.get_mut(key) {
+                state.duration = clip.duration;
+            }
+            self.state_machine.frame_progress.insert(
+                key.clone(),
+                clip.frames.iter().map(|x| x.progress).collect(),
+            );
+
+            let current_state = &mut self.state_machine.current_state;
+            if &current_state.key == key && current_state.duration != clip.duration {
+                current_state.elapsed = current_state.progress() * clip.duration;
+                current_state.duration = clip.duration;
+            }
+        }
+    }
+
+    /// Adds an animation layer on top of the existing layers.
+    ///
+    /// The layer's state machine is evaluated against the animator's parameters instead of its
+    /// own, so the triggers reset by one layer are reset for all of them. States without frames
+    /// hide the layer while they are active.
+    ///
+    /// Layers are updated after the animator's state machine, in the order they were added, and
+    /// what happened in them is returned in that order as [`AnimatorEvent::Layer`].
+    pub fn add_layer(
+        &mut self,
+        mut state_machine: StateMachine<K, V>,
+        state_frames: HashMap<K, Vec<Frame<F>>>,
+        blending: LayerBlending,
+    ) -> Result<(), AnimatorError<K>> {
+        for state in state_machine.states.keys() {
+            if let Some(frames) = state_frames.get(state) {
+                validate_frames(state, frames)?;
+            }
+        }
+
+        state_machine.frame_progress = frame_progress(&state_frames);
+        self.layers.push(AnimatorLayer {
+            state_machine,
+            state_frames,
+            blending,
+        });
+        Ok(())
+    }
+
+    /// Updates elapsed time, returning what happened in the state machine and the events of the
+    /// states crossed in order, followed by what happened in the layers
+    pub fn update(&mut self, delta_time: f32) -> Vec<AnimatorEvent<K, E>>
+    where
+        E: Clone,
+    {
+        let steps = self.state_machine.advance(delta_time);
+        self.pick_blend_clips();
+        let layer_events = self.update_layers(|x| x.update(delta_time));
+
+        let mut events = Vec::new();
+        for step in steps {
+            let sweep = match step {
+                Step::Event(event) => {
+                    events.push(AnimatorEvent::State(event));
+                    continue;
+                }
+                Step::Sweep(sweep) => sweep,
+            };
+            let Some(state_events) = self.state_events.get(&sweep.key) else {
+                continue;
+            };
+            let crossed = state_events.iter().filter(|x| sweep.contains(x.progress));
+            let crossed = crossed.map(|x| AnimatorEvent::Frame(x.event.clone()));
+            if sweep.reversed() {
+                events.extend(crossed.rev());
+            } else {
+                events.extend(crossed);
+            }
+        }
+        events.extend(layer_events);
+        events
+    }
 
-    /// Updates elapsed time
-    pub fn update(&mut self, delta_time: f32) {
-        self.state_machine.update(delta_time);
+    /// Updates the parameters, returning what happened in the state machine in order, followed by
+    /// what happened in the layers
+    pub fn update_parameters(&mut self, update: &dyn Fn(&mut V)) -> Vec<AnimatorEvent<K, E>> {
+        let events = self.state_machine.update_parameters(update);
+        self.pick_blend_clips();
+        let layer_events = self.update_layers(|x| x.update_parameters(&|_| {}));
+        events
+            .into_iter()
+            .map(AnimatorEvent::State)
+            .chain(layer_events)
+            .collect()
     }
 
-    /// Updates the parameters
-    pub fn update_parameters(&mut self, update: &dyn Fn(&mut V)) {
-        self.state_machine.update_parameters(update);
+    /// Fires a one-shot event, returning what happened in the state machine in order, followed by
+    /// what happened in the layers, see [`StateMachine::fire`]
+    pub fn fire(&mut self, event: impl Into<String>) -> Vec<AnimatorEvent<K, E>> {
+        let event = event.into();
+        let events = self.state_machine.fire(event.clone());
+        self.pick_blend_clips();
+        let layer_events = self.update_layers(|x| x.fire(event.clone()));
+        events
+            .into_iter()
+            .map(AnimatorEvent::State)
+            .chain(layer_events)
+            .collect()
+    }
+
+    /// Runs the state machines of the layers against the animator's parameters in order,
+    /// returning what happened in them
+    fn update_layers(
+        &mut self,
+        update: impl Fn(&mut StateMachine<K, V>) -> Vec<StateMachineEvent<K>>,
+    ) -> Vec<AnimatorEvent<K, E>> {
+        let mut events = Vec::new();
+        for (index, layer) in self.layers.iter_mut().enumerate() {
+            let layer_events = with_parameters(
+                &mut layer.state_machine,
+                &mut self.state_machine.parameters,
+                &update,
+            );
+            events.extend(
+                layer_events
+                    .into_iter()
+                    .map(|x| AnimatorEvent::Layer(index, x)),
+            );
+        }
+        events
+    }
+
+    /// Forces the state machine into a state, see [`StateMachine::play`]
+    pub fn play(&mut self, key: K) -> Result<Vec<StateMachineEvent<K>>, StateMachineError<K>> {
+        let events = self.state_machine.play(key)?;
+        self.pick_blend_clips();
+        Ok(events)
+    }
+
+    /// Forces the state machine into a state at a progress, see [`StateMachine::play_at`]
+    pub fn play_at(
+        &mut self,
+        key: K,
+        progress: f32,
+    ) -> Result<Vec<StateMachineEvent<K>>, StateMachineError<K>> {
+        let events = self.state_machine.play_at(key, progress)?;
+        self.pick_blend_clips();
+        Ok(events)
+    }
+
+    /// Restarts the current state from its beginning, see [`StateMachine::restart`]
+    pub fn restart(&mut self) -> Vec<StateMachineEvent<K>> {
+        let events = self.state_machine.restart();
+        self.pick_blend_clips();
+        events
+    }
+
+    /// Returns the playback speed multiplier of all states
+    pub fn speed(&self) -> f32 {
+        self.state_machine.speed()
+    }
+
+    /// Sets the playback speed multiplier of all states and layers, negative to play backwards
+    /// and zero to pause
+    pub fn set_speed(&mut self, speed: f32) {
+        for layer in &mut self.layers {
+            layer.state_machine.set_speed(speed);
+        }
+        self.state_machine.set_speed(speed);
+    }
+
+    /// Sets when transitions are checked on time updates of all layers, see
+    /// [`StateMachine::set_evaluation_policy`]
+    pub fn set_evaluation_policy(&mut self, evaluation_policy: EvaluationPolicy) {
+        for layer in &mut self.layers {
+            layer.state_machine.set_evaluation_policy(evaluation_policy);
+        }
+        self.state_machine.set_evaluation_policy(evaluation_policy);
+    }
+
+    /// Sets whether [`StateMachineEvent::Ambiguous`] is reported by all layers, see
+    /// [`StateMachine::set_report_ambiguities`]
+    pub fn set_report_ambiguities(&mut self, report_ambiguities: bool) {
+        for layer in &mut self.layers {
+            layer
+                .state_machine
+                .set_report_ambiguities(report_ambiguities);
+        }
+        self.state_machine
+            .set_report_ambiguities(report_ambiguities);
     }
 
     /// Returns the current state
@@ -459,33 +711,219 @@ where
         self.state_machine.state()
     }
 
+    /// Returns the current state of a layer, in the order the layers were added
+    pub fn layer_state(&self, layer: usize) -> Option<&CurrentState<K>> {
+        self.layers.get(layer).map(|x| x.state_machine.state())
+    }
+
     /// Updates the parameters
     pub fn parameters(&self) -> &V {
         self.state_machine.parameters()
     }
 
+    /// Returns the current crossfade, if a blended transition is in progress
+    pub fn blend(&self) -> Option<&Blend<K>> {
+        self.state_machine.blend()
+    }
+
     /// Returns the current frame
     pub fn frame(&self) -> &F {
-        let current_state = self.state_machine.state();
-        let frames = match self.state_frames.get(&current_state.key) {
-            Some(frames) => frames,
-            None => unreachable!(),
+        self.base_frame(self.state_machine.state())
+    }
+
+    /// Returns the current frame of every visible layer, bottom to top.
+    ///
+    /// An [`LayerBlending::Override`] layer hides the frames of the layers below it.
+    pub fn frames(&self) -> Vec<&F> {
+        let mut frames = vec![self.frame()];
+        for layer in &self.layers {
+            if let Some(frame) = state_frame(&layer.state_frames, layer.state_machine.state()) {
+                if layer.blending == LayerBlending::Override {
+                    frames.clear();
+                }
+                frames.push(frame);
+            }
+        }
+        frames
+    }
+
+    /// Returns the current frame, and the outgoing frame with its weight while a blended
+    /// transition is in progress
+    pub fn blended_frame(&self) -> (&F, Option<(&F, f32)>) {
+        (
+            self.frame(),
+            self.state_machine
+                .blend()
+                .map(|blend| (self.base_frame(&blend.from), blend.weight())),
+        )
+    }
+
+    fn base_frame(&self, state: &CurrentState<K>) -> &F {
+        let clip = self
+            .blend_spaces
+            .get(&state.key)
+            .and_then(|x| x.clip(&self.state_machine.parameters));
+        let frame = match clip {
+            Some(clip) => progress_frame(&clip.frames, state.progress()),
+            None => state_frame(&self.state_frames, state),
         };
+        match frame {
+            Some(frame) => frame,
+            None => unreachable!(),
+        }
+    }
+}
+
+fn validate_frames<K, F>(state: &K, frames: &[Frame<F>]) -> Result<(), AnimatorError<K>>
+where
+    K: Clone,
+{
+    if frames.is_empty() {
+        return Err(AnimatorError::EmptyStateFrames(state.clone()));
+    }
+
+    // make sure frames are sorted by progress
+    let mut last_progress = -1.0;
+    for frame in frames {
+        if frame.progress < last_progress {
+            return Err(AnimatorError::UnsortedStateFrames(state.clone()));
+        }
+        if frame.progress < 0.0 || frame.progress > 1.0 {
+            return Err(AnimatorError::InvalidStateFrameProgress(
+                state.clone(),
+                frame.progress,
+            ));
+        }
+        last_progress = frame.progress;
+    }
+    Ok(())
+}
+
+fn state_frame<'a, K, F>(
+    state_frames: &'a HashMap<K, Vec<Frame<F>>>,
+    state: &CurrentState<K>,
+) -> Option<&'a F>
+where
+    K: Eq + Hash,
+{
+    progress_frame(state_frames.get(&state.key)?, state.progress())
+}
+
+/// Returns the progress of every frame, for [`TransitionTrigger::FrameReached`]
+fn frame_progress<K, F>(state_frames: &HashMap<K, Vec<Frame<F>>>) -> HashMap<K, Vec<f32>>
+where
+    K: Clone + Eq + Hash,
+{
+    state_frames
+        .iter()
+        .map(|(key, frames)| (key.clone(), frames.iter().map(|x| x.progress).collect()))
+        .collect()
+}
+
+fn progress_frame<F>(frames: &[Frame<F>], progress: f32) -> Option<&F> {
+    let mut frame = frames.first()?;
+    for f in frames {
+        if f.progress > progress {
+            return Some(&frame.value);
+        }
+        frame = f;
+    }
+    Some(&frame.value)
+}
+
+/// An animation layer
+#[derive(Clone, Debug)]
+struct AnimatorLayer<K, V, F> {
+    state_machine: StateMachine<K, V>,
+    state_frames: HashMap<K, Vec<Frame<F>>>,
+    blending: LayerBlending,
+}
+
+/// Runs a layer's state machine against the animator's parameters
+fn with_parameters<K, V, T>(
+    state_machine: &mut StateMachine<K, V>,
+    parameters: &mut V,
+    run: impl FnOnce(&mut StateMachine<K, V>) -> T,
+) -> T {
+    std::mem::swap(&mut state_machine.parameters, parameters);
+    let result = run(state_machine);
+    std::mem::swap(&mut state_machine.parameters, parameters);
+    result
+}
+
+/// A set of clips a state picks its frames and duration from by a parameter value
+#[derive(Clone, Debug)]
+pub struct BlendSpace<V, F> {
+    /// The parameter value the clip closest to it is picked by
+    pub parameter: BlendParameter<V>,
+    /// The clips
+    pub clips: Vec<BlendClip<F>>,
+}
 
-        let progress = current_state.progress();
-        let mut frame = &frames[0];
-        for f in frames {
-            if f.progress > progress {
-                return &frame.value;
+impl<V, F> BlendSpace<V, F> {
+    /// Returns the clip closest to the parameter value
+    pub fn clip(&self, parameters: &V) -> Option<&BlendClip<F>> {
+        let (x, y) = match &self.parameter {
+            BlendParameter::Scalar(parameter) => (parameter(parameters), None),
+            BlendParameter::Vector(parameter) => {
+                let (x, y) = parameter(parameters);
+                (x, Some(y))
             }
-            frame = f;
+        };
+        let distance = |clip: &BlendClip<F>| match y {
+            Some(y) => (clip.position.0 - x).hypot(clip.position.1 - y),
+            None => (clip.position.0 - x).abs(),
+        };
+        self.clips
+            .iter()
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+    }
+}
+
+/// A blend space parameter
+#[derive(Clone)]
+pub enum BlendParameter<V> {
+    /// A 1D parameter, such as speed
+    Scalar(ParameterFn<V, f32>),
+    /// A 2D parameter, such as a direction
+    Vector(ParameterFn<V, (f32, f32)>),
+}
+
+type ParameterFn<V, T> = Arc<dyn Fn(&V) -> T + Send + Sync>;
+
+impl<V> Debug for BlendParameter<V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlendParameter::Scalar(_) => write!(f, "Scalar"),
+            BlendParameter::Vector(_) => write!(f, "Vector"),
         }
-        &frame.value
     }
 }
 
+/// A blend space clip
+#[derive(Clone, Debug)]
+pub struct BlendClip<F> {
+    /// The parameter value the clip is placed at, only the first value is used by
+    /// [`BlendParameter::Scalar`]
+    pub position: (f32, f32),
+    /// The clip duration
+    pub duration: f32,
+    /// The clip frames
+    pub frames: Vec<Frame<F>>,
+}
+
+/// How an animation layer combines with the layers below it
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum LayerBlending {
+    /// The layer's frame replaces the frames below it
+    Override,
+    /// The layer's frame is stacked on top of the frames below it
+    Additive,
+}
+
 /// An animation frame
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Frame<T> {
     /// When the frame should be displayed [0.0, 1.0).
     pub progress: f32,
@@ -493,6 +931,68 @@ pub struct Frame<T> {
     pub value: T,
 }
 
+impl<T> Frame<T> {
+    /// Converts frames with their own durations into frames displayed by progress
+    pub fn from_timed(frames: Vec<TimedFrame<T>>) -> Vec<Frame<T>> {
+        let duration = TimedFrame::total_duration(&frames);
+        let mut elapsed = 0.0;
+        frames
+            .into_iter()
+            .map(|x| {
+                let progress = if duration > 0.0 {
+                    elapsed / duration
+                } else {
+                    0.0
+                };
+                elapsed += x.duration;
+                Frame {
+                    progress,
+                    value: x.value,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Something that happened during an [`Animator`] update
+#[derive(Clone, PartialEq, Debug)]
+pub enum AnimatorEvent<K, E> {
+    /// Something that happened in the state machine
+    State(StateMachineEvent<K>),
+    /// An event of a state that was crossed, see [`FrameEvent`]
+    Frame(E),
+    /// Something that happened in the state machine of a layer, by the index of the layer in the
+    /// order the layers were added
+    Layer(usize, StateMachineEvent<K>),
+}
+
+/// An event of a state, fired when its progress is crossed
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameEvent<E> {
+    /// When the event should be fired [0.0, 1.0].
+    pub progress: f32,
+    /// The event payload.
+    pub event: E,
+}
+
+/// An animation frame with its own duration, as authored in tools like Aseprite
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimedFrame<T> {
+    /// How long the frame should be displayed.
+    pub duration: f32,
+    /// The frame value.
+    pub value: T,
+}
+
+impl<T> TimedFrame<T> {
+    /// Returns the total duration of the frames, which is the duration of their state
+    pub fn total_duration(frames: &[TimedFrame<T>]) -> f32 {
+        frames.iter().map(|x| x.duration).sum()
+    }
+}
+
 /// A animator error
 #[derive(Clone, PartialEq, Debug)]
 pub enum AnimatorError<K> {
@@ -504,6 +1004,18 @@ pub enum AnimatorError<K> {
     UnsortedStateFrames(K),
     /// The state frame progress is invalid.
     InvalidStateFrameProgress(K, f32),
+    /// The state's blend space has no clips.
+    EmptyBlendSpace(K),
+    /// The state's blend space has a clip with a non-positive duration.
+    InvalidBlendClipDuration(K, f32),
+    /// The state frame duration is negative.
+    InvalidStateFrameDuration(K, f32),
+    /// The total duration of the state frames does not match the state duration.
+    MismatchedStateFrameDuration(K, f32),
+    /// The state events should be sorted by progress.
+    UnsortedStateEvents(K),
+    /// The state event progress is invalid.
+    InvalidStateEventProgress(K, f32),
 }
 
 /// The state machine.
@@ -527,32 +1039,20 @@ pub enum AnimatorError<K> {
 /// let mut state_machine = StateMachine::new(
 ///     Animation::Idle,
 ///     HashMap::from([
-///         (
-///             Animation::Idle,
-///             State {
-///                 duration: 0.5,
-///                 repeat: true,
-///             },
-///         ),
-///         (
-///             Animation::Run,
-///             State {
-///                 duration: 1.0,
-///                 repeat: true,
-///             },
-///         ),
+///         (Animation::Idle, State::new(0.5, true)),
+///         (Animation::Run, State::new(1.0, true)),
 ///     ]),
 ///     vec![
-///         Transition {
-///             start_state: TransitionStartState::Node(Animation::Idle),
-///             end_state: TransitionEndState::Node(Animation::Run),
-///             trigger: TransitionTrigger::Condition(Box::new(|x: &Params| x.speed > 0.0)),
-///         },
-///         Transition {
-///             start_state: TransitionStartState::Node(Animation::Run),
-///             end_state: TransitionEndState::Node(Animation::Idle),
-///             trigger: TransitionTrigger::Condition(Box::new(|x: &Params| x.speed <= 0.0)),
-///         },
+///         Transition::new(
+///             TransitionStartState::Node(Animation::Idle),
+///             TransitionEndState::Node(Animation::Run),
+///             TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed > 0.0)),
+///         ),
+///         Transition::new(
+///             TransitionStartState::Node(Animation::Run),
+///             TransitionEndState::Node(Animation::Idle),
+///             TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed <= 0.0)),
+///         ),
 ///     ],
 ///     Params { speed: 0.0 },
 /// )
@@ -570,6 +1070,48 @@ pub struct StateMachine<K, V> {
     pub(crate) states: HashMap<K, State>,
     pub(crate) transitions: Vec<Transition<K, V>>,
     pub(crate) parameters: V,
+    pub(crate) events: Vec<PendingEvent>,
+    pub(crate) event_lifetime: u32,
+    pub(crate) speed: f32,
+    pub(crate) blend: Option<Blend<K>>,
+    pub(crate) scopes: Vec<Option<K>>,
+    pub(crate) parents: HashMap<K, K>,
+    pub(crate) entry_states: HashMap<K, K>,
+    pub(crate) latched: HashSet<usize>,
+    pub(crate) history: Vec<CurrentState<K>>,
+    pub(crate) history_depth: usize,
+    pub(crate) rng: u64,
+    pub(crate) report_ambiguities: bool,
+    pub(crate) previous_state: Option<K>,
+    pub(crate) time_in_state: f32,
+    pub(crate) evaluation_policy: EvaluationPolicy,
+    pub(crate) frame_progress: HashMap<K, Vec<f32>>,
+}
+
+/// The seed of the random number generator of a new [`StateMachine`]
+const DEFAULT_SEED: u64 = 0x2545_f491_4f6c_dd1d;
+
+/// A nested state machine, entered through its parent state.
+///
+/// Entering the parent state enters the entry state, and the parent state stays active for as
+/// long as any of its states is.
+#[derive(Clone, Debug)]
+pub struct SubStateMachine<K, V> {
+    /// The state entered when the parent state is entered
+    pub entry_state: K,
+    /// The states
+    pub states: HashMap<K, State>,
+    /// The nested state machines, keyed by their parent state
+    pub sub_machines: HashMap<K, SubStateMachine<K, V>>,
+    /// The local transitions, which may end in a state of any parent state machine
+    pub transitions: Vec<Transition<K, V>>,
+}
+
+/// An event that has been fired but not yet consumed
+#[derive(Clone, Debug)]
+pub(crate) struct PendingEvent {
+    pub(crate) name: String,
+    pub(crate) updates: u32,
 }
 
 impl<K, V> StateMachine<K, V>
@@ -583,42 +1125,224 @@ where
         transitions: Vec<Transition<K, V>>,
         parameters: V,
     ) -> Result<Self, StateMachineError<K>> {
+        Self::new_nested(
+            starting_state,
+            states,
+            HashMap::new(),
+            transitions,
+            parameters,
+        )
+    }
+
+    /// Creates a new [`StateMachine`] with nested [`SubStateMachine`]s, keyed by their parent state.
+    ///
+    /// State keys are shared by all machines and must be unique.
+    pub fn new_nested(
+        starting_state: K,
+        states: HashMap<K, State>,
+        sub_machines: HashMap<K, SubStateMachine<K, V>>,
+        transitions: Vec<Transition<K, V>>,
+        parameters: V,
+    ) -> Result<Self, StateMachineError<K>> {
+        let mut state_machine = Self {
+            current_state: CurrentState {
+                key: starting_state.clone(),
+                duration: 0.0,
+                elapsed: 0.0,
+                playback: Playback::Once,
+                speed: 1.0,
+                loops: 0,
+                direction: PlaybackDirection::Forward,
+                parents: Vec::new(),
+            },
+            states: HashMap::new(),
+            transitions: Vec::new(),
+            parameters,
+            events: Vec::new(),
+            event_lifetime: 1,
+            speed: 1.0,
+            blend: None,
+            scopes: Vec::new(),
+            parents: HashMap::new(),
+            entry_states: HashMap::new(),
+            latched: HashSet::new(),
+            history: Vec::new(),
+            history_depth: 1,
+            rng: DEFAULT_SEED,
+            report_ambiguities: false,
+            previous_state: None,
+            time_in_state: 0.0,
+            evaluation_policy: EvaluationPolicy::EveryUpdate,
+            frame_progress: HashMap::new(),
+        };
+        state_machine.add_machine(None, &HashSet::new(), states, sub_machines, transitions)?;
+
+        // validate that the tags transitions start in are declared by any state
+        for transition in &state_machine.transitions {
+            if let TransitionStartState::Tag(tag) = &transition.start_state
+                && !state_machine.states.values().any(|x| x.tags.contains(tag))
+            {
+                return Err(StateMachineError::InvalidTransitionStartTag(tag.clone()));
+            }
+        }
+
         // validate that the starting state exists
-        let start = match states.get(&starting_state) {
-            Some(state) => state,
-            None => {
-                return Err(StateMachineError::InvalidStartingState(starting_state));
+        if !state_machine.states.contains_key(&starting_state)
+            && !state_machine.entry_states.contains_key(&starting_state)
+        {
+            return Err(StateMachineError::InvalidStartingState(starting_state));
+        }
+        state_machine.reset_state(&starting_state);
+
+        Ok(state_machine)
+    }
+
+    /// Flattens a (sub) state machine into this state machine
+    fn add_machine(
+        &mut self,
+        scope: Option<K>,
+        ancestors: &HashSet<K>,
+        states: HashMap<K, State>,
+        sub_machines: HashMap<K, SubStateMachine<K, V>>,
+        transitions: Vec<Transition<K, V>>,
+    ) -> Result<(), StateMachineError<K>> {
+        // validate that state keys are unique across all machines
+        let mut keys = HashSet::new();
+        for key in states.keys().chain(sub_machines.keys()) {
+            if !keys.insert(key.clone())
+                || ancestors.contains(key)
+                || self.states.contains_key(key)
+                || self.entry_states.contains_key(key)
+            {
+                return Err(StateMachineError::DuplicateState(key.clone()));
             }
-        };
-        // validate that the start and end states of each transition exist
+        }
+        // validate that the start and end states of each transition exist, transitions may
+        // only start in their own machine but may end in any of its parents
         for transition in &transitions {
             match &transition.start_state {
-                TransitionStartState::Any => {}
+                TransitionStartState::Any | TransitionStartState::Tag(_) => {}
                 TransitionStartState::Node(key) => {
-                    if !states.contains_key(key) {
+                    if !keys.contains(key) {
+                        return Err(StateMachineError::InvalidTransitionStartState(key.clone()));
+                    }
+                }
+                TransitionStartState::AnyOf(start_keys)
+                | TransitionStartState::AnyExcept(start_keys) => {
+                    if let Some(key) = start_keys.iter().find(|x| !keys.contains(*x)) {
                         return Err(StateMachineError::InvalidTransitionStartState(key.clone()));
                     }
                 }
             }
             match &transition.end_state {
+                TransitionEndState::Previous { .. } => {}
+                TransitionEndState::Random(targets) => {
+                    if let Some((key, _)) = targets
+                        .iter()
+                        .find(|(x, _)| !keys.contains(x) && !ancestors.contains(x))
+                    {
+                        return Err(StateMachineError::InvalidTransitionEndState(key.clone()));
+                    }
+                    if targets.iter().all(|(_, x)| *x == 0) {
+                        return Err(StateMachineError::InvalidTransitionEndWeights);
+                    }
+                }
                 TransitionEndState::Node(key) => {
-                    if !states.contains_key(key) {
+                    if !keys.contains(key) && !ancestors.contains(key) {
                         return Err(StateMachineError::InvalidTransitionEndState(key.clone()));
                     }
                 }
             }
+            if let Some(exit_time) = transition.exit_time
+                && !(0.0..=1.0).contains(&exit_time)
+            {
+                return Err(StateMachineError::InvalidTransitionExitTime(exit_time));
+            }
+            if let Some(blend_duration) = transition.blend_duration
+                && blend_duration <= 0.0
+            {
+                return Err(StateMachineError::InvalidTransitionBlendDuration(
+                    blend_duration,
+                ));
+            }
         }
-        Ok(Self {
-            current_state: CurrentState {
-                key: starting_state,
-                duration: start.duration,
-                elapsed: 0.0,
-                repeat: start.repeat,
-            },
-            states,
-            transitions,
-            parameters,
-        })
+
+        if let Some(scope) = &scope {
+            for key in &keys {
+                self.parents.insert(key.clone(), scope.clone());
+            }
+        }
+        self.states.extend(states);
+        self.scopes
+            .extend(transitions.iter().map(|_| scope.clone()));
+        self.transitions.extend(transitions);
+
+        let ancestors = ancestors
+            .iter()
+            .cloned()
+            .chain(keys)
+            .collect::<HashSet<_>>();
+        for (key, sub_machine) in sub_machines {
+            // validate that the entry state belongs to the sub state machine
+            if !sub_machine.states.contains_key(&sub_machine.entry_state)
+                && !sub_machine
+                    .sub_machines
+                    .contains_key(&sub_machine.entry_state)
+            {
+                return Err(StateMachineError::InvalidEntryState(
+                    sub_machine.entry_state,
+                ));
+            }
+            self.entry_states
+                .insert(key.clone(), sub_machine.entry_state);
+            self.add_machine(
+                Some(key),
+                &ancestors,
+                sub_machine.states,
+                sub_machine.sub_machines,
+                sub_machine.transitions,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Resets the current state to the given state, or to the entry state of the given sub state
+    /// machine
+    fn reset_state(&mut self, key: &K) {
+        let mut key = key;
+        while let Some(entry_state) = self.entry_states.get(key) {
+            key = entry_state;
+        }
+        let state = match self.states.get(key) {
+            Some(state) => state,
+            None => unreachable!(),
+        };
+
+        let mut parents = Vec::new();
+        let mut parent = self.parents.get(key);
+        while let Some(key) = parent {
+            parents.push(key.clone());
+            parent = self.parents.get(key);
+        }
+        parents.reverse();
+
+        // States played backwards start at their end
+        let speed = state.speed * self.speed;
+        let (elapsed, direction) = match speed < 0.0 {
+            true => (state.duration, PlaybackDirection::Backward),
+            false => (0.0, PlaybackDirection::Forward),
+        };
+        self.current_state = CurrentState {
+            key: key.clone(),
+            duration: state.duration,
+            elapsed,
+            playback: state.playback,
+            speed,
+            loops: 0,
+            direction,
+            parents,
+        };
     }
 
     /// Returns the current state
@@ -631,118 +1355,697 @@ where
         &self.parameters
     }
 
-    fn transition(&mut self) {
-        let mut visited = HashSet::new();
+    /// Returns the current crossfade, if a blended transition is in progress
+    pub fn blend(&self) -> Option<&Blend<K>> {
+        self.blend.as_ref()
+    }
 
-        loop {
-            let start_state = TransitionStartState::Node(self.current_state.key.clone());
-            let state_ended = self.current_state.elapsed >= self.current_state.duration;
-            if let Some(transition) = self.transitions.iter().find(|x| {
-                (x.start_state == start_state || x.start_state == TransitionStartState::Any)
-                    && match &x.end_state {
-                        TransitionEndState::Node(node) => node != &self.current_state.key,
+    /// Returns the number of updates a fired event stays pending
+    pub fn event_lifetime(&self) -> u32 {
+        self.event_lifetime
+    }
+
+    /// Sets the number of updates a fired event stays pending before it is dropped
+    pub fn set_event_lifetime(&mut self, updates: u32) {
+        self.event_lifetime = updates;
+    }
+
+    /// Returns the number of previously active states remembered for
+    /// [`TransitionEndState::Previous`]
+    pub fn history_depth(&self) -> usize {
+        self.history_depth
+    }
+
+    /// Sets the number of previously active states remembered for
+    /// [`TransitionEndState::Previous`], forgetting the oldest states if needed
+    pub fn set_history_depth(&mut self, depth: usize) {
+        self.history_depth = depth;
+        let excess = self.history.len().saturating_sub(depth);
+        self.history.drain(..excess);
+    }
+
+    /// Returns the previously active states, the most recent last
+    pub fn history(&self) -> &[CurrentState<K>] {
+        &self.history
+    }
+
+    /// Returns the playback speed multiplier of all states
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Sets the playback speed multiplier of all states, negative to play backwards and zero to
+    /// pause
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+
+        // Changing the sign of the speed reverses the current direction
+        let states = &self.states;
+        let set_state_speed = |state: &mut CurrentState<K>| {
+            let state_speed = match states.get(&state.key) {
+                Some(x) => x.speed * speed,
+                None => unreachable!(),
+            };
+            if (state_speed < 0.0) != (state.speed < 0.0) {
+                state.direction = state.direction.reversed();
+            }
+            state.speed = state_speed;
+        };
+        set_state_speed(&mut self.current_state);
+        if let Some(blend) = &mut self.blend {
+            set_state_speed(&mut blend.from);
+        }
+    }
+
+    fn is_triggered(&self, trigger: &TransitionTrigger<K, V>, state_ended: bool) -> bool {
+        match trigger {
+            TransitionTrigger::Condition(condition) => condition(&self.parameters),
+            TransitionTrigger::Compare(comparison) => comparison.matches(&self.parameters),
+            TransitionTrigger::Context(condition) => condition(&ConditionContext {
+                parameters: &self.parameters,
+                state: &self.current_state,
+                previous_state: self.previous_state.as_ref(),
+                time_in_state: self.time_in_state,
+            }),
+            TransitionTrigger::Event(event) => self.events.iter().any(|x| &x.name == event),
+            TransitionTrigger::End => state_ended,
+            TransitionTrigger::All(triggers) => {
+                triggers.iter().all(|x| self.is_triggered(x, state_ended))
+            }
+            TransitionTrigger::Any(triggers) => {
+                triggers.iter().any(|x| self.is_triggered(x, state_ended))
+            }
+            TransitionTrigger::Not(trigger) => !self.is_triggered(trigger, state_ended),
+            TransitionTrigger::After(seconds) => self.time_in_state >= *seconds,
+            TransitionTrigger::Loops(loops) => self.current_state.loops >= *loops,
+            TransitionTrigger::FrameReached(index) => {
+                let state = &self.current_state;
+                let progress = state.progress();
+                self.frame_progress
+                    .get(&state.key)
+                    .filter(|frames| *index < frames.len())
+                    .is_some_and(|frames| match state.direction {
+                        PlaybackDirection::Forward => progress >= frames[*index],
+                        PlaybackDirection::Backward => {
+                            frames.get(index + 1).is_none_or(|x| progress < *x)
+                        }
+                    })
+            }
+        }
+    }
+
+    fn find_transition(
+        &self,
+        transitions: fn(&Transition<K, V>) -> bool,
+        state_ended: bool,
+        steps: &mut Vec<Step<K>>,
+    ) -> Option<usize> {
+        let progress = self.current_state.played();
+        let active =
+            |key: &K| self.current_state.key == *key || self.current_state.parents.contains(key);
+        // Only transitions of the active state machines can fire, the root having depth 0
+        let depth = |scope: &Option<K>| match scope {
+            None => Some(0),
+            Some(scope) => self
+                .current_state
+                .parents
+                .iter()
+                .position(|x| x == scope)
+                .map(|x| x + 1),
+        };
+
+        let matched = self
+            .transitions
+            .iter()
+            .zip(&self.scopes)
+            .enumerate()
+            .filter_map(|(index, (x, x_scope))| {
+                let depth = depth(x_scope)?;
+                // Whether the transition starts in specific states rather than any state
+                let specific = match &x.start_state {
+                    TransitionStartState::Any => Some(false),
+                    TransitionStartState::Node(node) => active(node).then_some(true),
+                    TransitionStartState::AnyOf(nodes) => nodes.iter().any(active).then_some(true),
+                    TransitionStartState::AnyExcept(nodes) => {
+                        (!nodes.iter().any(active)).then_some(false)
                     }
-                    && match &x.trigger {
-                        TransitionTrigger::Condition(condition) => condition(&self.parameters),
-                        TransitionTrigger::End => state_ended,
+                    TransitionStartState::Tag(tag) => self
+                        .current_state
+                        .path()
+                        .filter_map(|x| self.states.get(x))
+                        .any(|x| x.tags.contains(tag))
+                        .then_some(true),
+                }?;
+                let fires = match &x.end_state {
+                    TransitionEndState::Random(_) => {
+                        !x.self_transition || !self.latched.contains(&index)
                     }
-            }) {
-                let TransitionEndState::Node(end_state_key) = &transition.end_state;
+                    _ => self.end_state_key(x).is_some_and(|node| {
+                        !active(node) || x.self_transition && !self.latched.contains(&index)
+                    }),
+                } && transitions(x)
+                    && (state_ended || x.exit_time.is_none_or(|exit_time| progress >= exit_time))
+                    && self.is_triggered(&x.trigger, state_ended);
+                fires.then_some((
+                    (
+                        Reverse(x.priority),
+                        Reverse(depth),
+                        Reverse(specific),
+                        index,
+                    ),
+                    index,
+                ))
+            });
 
-                if visited.contains(end_state_key) {
-                    // We have already visited this state, so we should stop
-                    break;
+        if !self.report_ambiguities {
+            return matched
+                .min_by_key(|(order, _)| *order)
+                .map(|(_, index)| index);
+        }
+
+        let mut matched = matched.collect::<Vec<_>>();
+        matched.sort_by_key(|(order, _)| *order);
+        if matched.len() > 1 {
+            steps.push(Step::Event(StateMachineEvent::Ambiguous {
+                transitions: matched.iter().map(|(_, index)| *index).collect(),
+            }));
+        }
+        matched.first().map(|(_, index)| *index)
+    }
+
+    /// Enters the end state of a transition, returning whether the state changed
+    fn enter(&mut self, index: usize, steps: &mut Vec<Step<K>>) -> bool {
+        // Picking the active state of a random transition keeps playing it
+        let random = match &self.transitions[index].end_state {
+            TransitionEndState::Random(targets) => {
+                let targets = targets.clone();
+                let key = self.pick(&targets).clone();
+                if !self.transitions[index].self_transition
+                    && self.current_state.path().any(|x| *x == key)
+                {
+                    return false;
                 }
+                Some(key)
+            }
+            _ => None,
+        };
 
-                let end_state = match self.states.get(end_state_key) {
-                    Some(state) => state,
-                    None => unreachable!(),
-                };
+        // Events and trigger parameters are consumed once the transition they cause changes state
+        for trigger in self.transitions[index].trigger.positive() {
+            match trigger {
+                TransitionTrigger::Event(event) => {
+                    if let Some(index) = self.events.iter().position(|x| &x.name == event) {
+                        self.events.remove(index);
+                    }
+                }
+                TransitionTrigger::Compare(comparison) => {
+                    (comparison.reset_trigger)(&mut self.parameters, &comparison.parameter);
+                }
+                _ => {}
+            }
+        }
 
-                self.current_state.key = end_state_key.clone();
-                self.current_state.duration = end_state.duration;
-                self.current_state.elapsed = 0.0;
-                self.current_state.repeat = end_state.repeat;
+        let transition = &self.transitions[index];
 
-                visited.insert(end_state_key.clone());
-            } else {
-                break;
+        // Blended transitions crossfade out of the state being left, others cut immediately
+        self.blend = transition.blend_duration.map(|duration| Blend {
+            from: self.current_state.clone(),
+            duration,
+            elapsed: 0.0,
+        });
+
+        // Conditional self-transitions are latched until their condition is false, so that they
+        // do not restart the state they entered on every update
+        if transition.self_transition
+            && transition
+                .trigger
+                .contains(&|x| !matches!(x, TransitionTrigger::Event(_) | TransitionTrigger::End))
+        {
+            self.latched.insert(index);
+        }
+
+        // Returning to the previous state takes it off the history instead of adding to it
+        let (key, resume) = match (transition.end_state.clone(), random) {
+            (_, Some(key)) | (TransitionEndState::Node(key), None) => {
+                self.remember();
+                (key, None)
             }
+            (TransitionEndState::Previous { resume }, None) => match self.history.pop() {
+                Some(state) => (state.key.clone(), resume.then_some(state)),
+                None => unreachable!(),
+            },
+            (TransitionEndState::Random(_), None) => unreachable!(),
+        };
+        self.switch_state(&key, Some(index), steps);
+
+        if let Some(state) = resume {
+            self.current_state.elapsed = state.elapsed;
+            self.current_state.loops = state.loops;
+            self.current_state.direction = state.direction;
         }
+        true
     }
 
-    /// Updates the parameters
-    pub fn update_parameters(&mut self, update: &dyn Fn(&mut V)) {
-        update(&mut self.parameters);
+    /// Picks one of the weighted states at random
+    fn pick<'a>(&mut self, targets: &'a [(K, u32)]) -> &'a K {
+        // xorshift64
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+
+        let total = targets.iter().map(|(_, x)| u64::from(*x)).sum::<u64>();
+        let mut pick = self.rng % total;
+        for (key, weight) in targets {
+            if pick < u64::from(*weight) {
+                return key;
+            }
+            pick -= u64::from(*weight);
+        }
+        unreachable!()
+    }
+
+    /// Seeds the random number generator used by [`TransitionEndState::Random`].
+    ///
+    /// The generator is part of the state machine, so a cloned state machine picks the same
+    /// states as the original.
+    pub fn set_seed(&mut self, seed: u64) {
+        // xorshift gets stuck on zero
+        self.rng = if seed == 0 { DEFAULT_SEED } else { seed };
+    }
+
+    /// Returns when transitions are checked on time updates
+    pub fn evaluation_policy(&self) -> EvaluationPolicy {
+        self.evaluation_policy
+    }
+
+    /// Sets when transitions are checked on time updates
+    pub fn set_evaluation_policy(&mut self, evaluation_policy: EvaluationPolicy) {
+        self.evaluation_policy = evaluation_policy;
+    }
+
+    /// Returns whether [`StateMachineEvent::Ambiguous`] is reported
+    pub fn report_ambiguities(&self) -> bool {
+        self.report_ambiguities
+    }
+
+    /// Sets whether [`StateMachineEvent::Ambiguous`] is reported when more than one transition
+    /// can fire at the same time.
+    ///
+    /// This is meant for debugging, as every transition has to be checked to find them.
+    pub fn set_report_ambiguities(&mut self, report_ambiguities: bool) {
+        self.report_ambiguities = report_ambiguities;
+    }
+
+    /// Returns the state a transition ends in, if any
+    fn end_state_key<'a>(&'a self, transition: &'a Transition<K, V>) -> Option<&'a K> {
+        match &transition.end_state {
+            TransitionEndState::Node(key) => Some(key),
+            TransitionEndState::Previous { .. } => self.history.last().map(|x| &x.key),
+            TransitionEndState::Random(_) => None,
+        }
+    }
+
+    /// Adds the current state to the history, forgetting the oldest state if it is full
+    fn remember(&mut self) {
+        self.history.push(self.current_state.clone());
+        if self.history.len() > self.history_depth {
+            self.history.remove(0);
+        }
+    }
+
+    /// Releases the latched self-transitions whose condition is false
+    fn release_latched(&mut self) {
+        let latched = self
+            .latched
+            .iter()
+            .copied()
+            .filter(|x| self.is_triggered(&self.transitions[*x].trigger, false))
+            .collect();
+        self.latched = latched;
+    }
 
-        let start_state = TransitionStartState::Node(self.current_state.key.clone());
+    /// Resets the current state to the given state, recording the exited and entered states
+    fn switch_state(&mut self, key: &K, transition: Option<usize>, steps: &mut Vec<Step<K>>) {
+        let from = self.current_state.path().cloned().collect::<Vec<_>>();
+        self.previous_state = Some(self.current_state.key.clone());
+        self.time_in_state = 0.0;
+        self.reset_state(key);
+        let to = self.current_state.path().cloned().collect::<Vec<_>>();
 
-        // Only trigger conditional transitions since the time has not changed
-        if let Some(transition) = self.transitions.iter().find(|x| {
-            (x.start_state == start_state || x.start_state == TransitionStartState::Any)
-                && match &x.end_state {
-                    TransitionEndState::Node(node) => node != &self.current_state.key,
+        // Only the states that are not shared by both paths are exited and entered, but the
+        // given state is always re-entered
+        let shared = from.iter().zip(&to).take_while(|(x, y)| x == y).count();
+        let shared = match from.iter().position(|x| x == key) {
+            Some(position) => shared.min(position),
+            None => shared,
+        };
+        for key in from[shared..].iter().rev() {
+            steps.push(Step::Event(StateMachineEvent::Exited(key.clone())));
+        }
+        if let Some(index) = transition {
+            steps.push(Step::Event(StateMachineEvent::TransitionFired {
+                from: from[from.len() - 1].clone(),
+                to: self.current_state.key.clone(),
+                index,
+            }));
+        }
+        for key in &to[shared..] {
+            steps.push(Step::Event(StateMachineEvent::Entered(key.clone())));
+        }
+    }
+
+    /// Forces the state machine into a state, or into the entry state of a sub state machine,
+    /// without a transition.
+    ///
+    /// Any transitions of the new state are resolved right away. Returns what happened in order.
+    pub fn play(&mut self, key: K) -> Result<Vec<StateMachineEvent<K>>, StateMachineError<K>> {
+        self.force_state(key, None)
+    }
+
+    /// Forces the state machine into a state at a progress [0.0, 1.0], see
+    /// [`StateMachine::play`]
+    pub fn play_at(
+        &mut self,
+        key: K,
+        progress: f32,
+    ) -> Result<Vec<StateMachineEvent<K>>, StateMachineError<K>> {
+        if !(0.0..=1.0).contains(&progress) {
+            return Err(StateMachineError::InvalidStateProgress(progress));
+        }
+        self.force_state(key, Some(progress))
+    }
+
+    fn force_state(
+        &mut self,
+        key: K,
+        progress: Option<f32>,
+    ) -> Result<Vec<StateMachineEvent<K>>, StateMachineError<K>> {
+        if !self.states.contains_key(&key) && !self.entry_states.contains_key(&key) {
+            return Err(StateMachineError::InvalidState(key));
+        }
+
+        let mut steps = Vec::new();
+        self.blend = None;
+        self.remember();
+        self.switch_state(&key, None, &mut steps);
+        if let Some(progress) = progress {
+            self.current_state.elapsed = progress * self.current_state.duration;
+        }
+
+        // Make sure we transition through any transitions
+        self.transition(&mut steps);
+        Ok(Step::events(steps))
+    }
+
+    /// Restarts the current state from its beginning, see [`StateMachine::play`]
+    pub fn restart(&mut self) -> Vec<StateMachineEvent<K>> {
+        let mut steps = Vec::new();
+        self.blend = None;
+        self.switch_state(&self.current_state.key.clone(), None, &mut steps);
+
+        // Make sure we transition through any transitions
+        self.transition(&mut steps);
+        Step::events(steps)
+    }
+
+    fn transition(&mut self, steps: &mut Vec<Step<K>>) {
+        let mut visited = HashSet::new();
+
+        loop {
+            let state_ended = self.current_state.ended();
+            if let Some(transition) = self.find_transition(|_| true, state_ended, steps) {
+                // The end state of a random transition is only known once it is entered
+                if let Some(end_state_key) = self.end_state_key(&self.transitions[transition]) {
+                    if visited.contains(end_state_key) {
+                        // We have already visited this state, so we should stop
+                        break;
+                    }
+                    visited.insert(end_state_key.clone());
                 }
-                && match &x.trigger {
-                    TransitionTrigger::Condition(condition) => condition(&self.parameters),
-                    _ => false,
+
+                let random = matches!(
+                    self.transitions[transition].end_state,
+                    TransitionEndState::Random(_)
+                );
+                if !self.enter(transition, steps) {
+                    break;
                 }
-        }) {
-            let TransitionEndState::Node(end_state_key) = &transition.end_state;
-            let end_state = match self.states.get(end_state_key) {
-                Some(state) => state,
-                None => unreachable!(),
-            };
+                if random && !visited.insert(self.current_state.key.clone()) {
+                    break;
+                }
+            } else {
+                break;
+            }
+        }
+    }
 
-            self.current_state.key = end_state_key.clone();
-            self.current_state.duration = end_state.duration;
-            self.current_state.elapsed = 0.0;
-            self.current_state.repeat = end_state.repeat;
+    /// Updates the parameters, returning what happened in order.
+    ///
+    /// Only [`TransitionTrigger::Condition`], [`TransitionTrigger::Compare`],
+    /// [`TransitionTrigger::Context`] and [`TransitionTrigger::Event`] transitions are checked,
+    /// since the time has not changed. [`TransitionTrigger::All`], [`TransitionTrigger::Any`] and
+    /// [`TransitionTrigger::Not`] transitions are checked when they contain one of them.
+    pub fn update_parameters(&mut self, update: &dyn Fn(&mut V)) -> Vec<StateMachineEvent<K>> {
+        update(&mut self.parameters);
+        self.release_latched();
 
+        let mut steps = Vec::new();
+        // Only trigger conditional and event transitions since the time has not changed
+        if let Some(transition) = self.find_transition(
+            |x| {
+                x.trigger.contains(&|x| {
+                    matches!(
+                        x,
+                        TransitionTrigger::Condition(_)
+                            | TransitionTrigger::Compare(_)
+                            | TransitionTrigger::Context(_)
+                            | TransitionTrigger::Event(_)
+                    )
+                })
+            },
+            false,
+            &mut steps,
+        ) && self.enter(transition, &mut steps)
+        {
             // Make sure we transition through any more transitions
-            self.transition();
+            self.transition(&mut steps);
         };
+        Step::events(steps)
     }
 
-    /// Updates elapsed time
-    pub fn update(&mut self, delta_time: f32) {
-        if self.current_state.elapsed < self.current_state.duration {
-            self.current_state.elapsed += delta_time;
+    /// Fires a one-shot event.
+    ///
+    /// The event is consumed by the first [`TransitionTrigger::Event`] transition it causes.
+    /// If no transition uses it, it stays pending for [`StateMachine::event_lifetime`] updates.
+    /// Returns what happened in order.
+    pub fn fire(&mut self, event: impl Into<String>) -> Vec<StateMachineEvent<K>> {
+        self.events.push(PendingEvent {
+            name: event.into(),
+            updates: 0,
+        });
 
-            if self.current_state.elapsed >= self.current_state.duration {
-                if self.current_state.repeat {
-                    self.current_state.elapsed %= self.current_state.duration
-                } else {
-                    self.current_state.elapsed = self.current_state.duration;
+        // Only trigger event transitions since neither the time nor the parameters have changed
+        let mut steps = Vec::new();
+        if let Some(transition) = self.find_transition(
+            |x| {
+                x.trigger
+                    .contains(&|x| matches!(x, TransitionTrigger::Event(_)))
+            },
+            false,
+            &mut steps,
+        ) && self.enter(transition, &mut steps)
+        {
+            // Make sure we transition through any more transitions
+            self.transition(&mut steps);
+        }
+        Step::events(steps)
+    }
+
+    /// Updates elapsed time, returning what happened in order.
+    ///
+    /// Which transitions are checked depends on the [`EvaluationPolicy`].
+    pub fn update(&mut self, delta_time: f32) -> Vec<StateMachineEvent<K>> {
+        Step::events(self.advance(delta_time))
+    }
+
+    /// Updates elapsed time, returning what happened and the stretches of the states played in
+    /// order
+    pub(crate) fn advance(&mut self, delta_time: f32) -> Vec<Step<K>> {
+        // The outgoing state of a crossfade keeps playing until the crossfade completes
+        if let Some(blend) = &mut self.blend {
+            blend.elapsed += delta_time * self.speed.abs();
+            if blend.elapsed >= blend.duration {
+                self.blend = None;
+            } else {
+                let mut remaining = delta_time;
+                let mut resumed = false;
+                while let Some(overflow) = blend.from.advance(remaining, resumed, &mut Vec::new()) {
+                    remaining = blend.from.skip_loops(overflow, &mut Vec::new());
+                    resumed = true;
                 }
+            }
+        }
 
-                let start_state = TransitionStartState::Node(self.current_state.key.clone());
+        let transitions: fn(&Transition<K, V>) -> bool = match self.evaluation_policy {
+            EvaluationPolicy::EveryUpdate => |_| true,
+            // Only trigger transitions that do not solely depend on the parameters, since they
+            // have not changed
+            EvaluationPolicy::ParameterChanges => |x| {
+                x.exit_time.is_some()
+                    || x.trigger.contains(&|x| {
+                        !matches!(
+                            x,
+                            TransitionTrigger::Condition(_) | TransitionTrigger::Compare(_)
+                        )
+                    })
+            },
+        };
 
-                // Only trigger end transitions since the parameters have not changed
-                if let Some(transition) = self.transitions.iter().find(|x| {
-                    matches!(x.trigger, TransitionTrigger::End)
-                        && (x.start_state == start_state
-                            || x.start_state == TransitionStartState::Any)
-                        && match &x.end_state {
-                            TransitionEndState::Node(node) => node != &self.current_state.key,
-                        }
-                }) {
-                    let TransitionEndState::Node(end_state_key) = &transition.end_state;
-                    let end_state = match self.states.get(end_state_key) {
-                        Some(state) => state,
-                        None => unreachable!(),
-                    };
-
-                    self.current_state.key = end_state_key.clone();
-                    self.current_state.duration = end_state.duration;
-                    self.current_state.elapsed = 0.0;
-                    self.current_state.repeat = end_state.repeat;
-
-                    // Make sure we transition through any more transitions
-                    self.transition();
+        let mut steps = Vec::new();
+        let mut remaining = delta_time;
+        let mut visited = HashSet::new();
+        let mut resumed = false;
+        loop {
+            let overflow = self.current_state.advance(remaining, resumed, &mut steps);
+
+            self.time_in_state += remaining - overflow.unwrap_or(0.0);
+            self.release_latched();
+
+            let transition = self.find_transition(
+                transitions,
+                overflow.is_some() || self.current_state.ended(),
+                &mut steps,
+            );
+            if !transition.is_some_and(|x| self.enter(x, &mut steps)) {
+                // A repeating state that stays plays the time left over as its next loops
+                match overflow {
+                    Some(overflow) if overflow > 0.0 => {
+                        remaining = self.current_state.skip_loops(overflow, &mut steps);
+                        self.time_in_state += overflow - remaining;
+                        resumed = true;
+                        continue;
+                    }
+                    _ => break,
                 }
             }
+
+            // Make sure we transition through any more transitions
+            self.transition(&mut steps);
+
+            // Time left over past the end of the previous state is carried into the next one
+            let Some(overflow) = overflow else {
+                break;
+            };
+
+            // Guard against cycles of states that do not consume any time
+            if overflow < remaining {
+                visited.clear();
+            }
+            if !visited.insert(self.current_state.key.clone()) {
+                break;
+            }
+            remaining = overflow;
+            resumed = false;
         }
+
+        // Drop events that have outlived their lifetime without causing a transition
+        let event_lifetime = self.event_lifetime;
+        self.events.retain_mut(|x| {
+            x.updates += 1;
+            x.updates < event_lifetime
+        });
+        steps
+    }
+}
+
+/// Which transitions are checked on time updates, see [`StateMachine::update`]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EvaluationPolicy {
+    /// All transitions are checked, so a condition that is already true fires as soon as
+    /// nothing else blocks it
+    EveryUpdate,
+    /// Transitions triggered by nothing but [`TransitionTrigger::Condition`] and
+    /// [`TransitionTrigger::Compare`] are left to parameter updates, unless they are exit time
+    /// gated
+    ParameterChanges,
+}
+
+/// Something that happened in a state machine
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum StateMachineEvent<K> {
+    /// A state was exited, nested states before their parent states
+    Exited(K),
+    /// A state was entered, parent states before their nested states
+    Entered(K),
+    /// A repeating state completed a loop, or several whole loops that an update skipped at once
+    Looped(K),
+    /// A state completed all of its loops
+    Finished(K),
+    /// A transition fired
+    TransitionFired {
+        /// The state that was exited
+        from: K,
+        /// The state that was entered
+        to: K,
+        /// The index of the transition, in the order the transitions were declared with
+        /// nested state machines' transitions following their parents'
+        index: usize,
+    },
+    /// More than one transition could fire at the same time, see
+    /// [`StateMachine::set_report_ambiguities`]
+    Ambiguous {
+        /// The indices of the transitions in the order they take precedence, the first being
+        /// the one chosen, see [`Transition::priority`]
+        transitions: Vec<usize>,
+    },
+}
+
+/// Something that happened or a stretch of a state played during an update
+#[derive(Clone, Debug)]
+pub(crate) enum Step<K> {
+    Event(StateMachineEvent<K>),
+    Sweep(Sweep<K>),
+}
+
+impl<K> Step<K> {
+    fn events(steps: Vec<Step<K>>) -> Vec<StateMachineEvent<K>> {
+        steps
+            .into_iter()
+            .filter_map(|x| match x {
+                Step::Event(event) => Some(event),
+                Step::Sweep(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// A stretch of a state played during an update, in progress
+#[derive(Clone, Debug)]
+pub(crate) struct Sweep<K> {
+    pub(crate) key: K,
+    pub(crate) from: f32,
+    pub(crate) to: f32,
+    /// Whether `from` was already swept by the previous stretch
+    pub(crate) from_swept: bool,
+    /// Whether `to` is swept as well, which is the case when it is a boundary of the state
+    pub(crate) to_swept: bool,
+}
+
+impl<K> Sweep<K> {
+    /// Returns whether the progress was swept
+    pub(crate) fn contains(&self, progress: f32) -> bool {
+        let (start, end, start_swept, end_swept) = match self.from <= self.to {
+            true => (self.from, self.to, !self.from_swept, self.to_swept),
+            false => (self.to, self.from, self.to_swept, !self.from_swept),
+        };
+        (progress > start || start_swept && progress == start)
+            && (progress < end || end_swept && progress == end)
+    }
+
+    /// Returns whether the state was played backwards
+    pub(crate) fn reversed(&self) -> bool {
+        self.from > self.to
     }
 }
 
@@ -755,10 +2058,27 @@ pub enum StateMachineError<K> {
     InvalidTransitionStartState(K),
     /// The end state of a transition does not exist
     InvalidTransitionEndState(K),
+    /// A state key is used more than once
+    DuplicateState(K),
+    /// The entry state of a sub state machine does not exist
+    InvalidEntryState(K),
+    /// The exit time of a transition is outside [0.0, 1.0]
+    InvalidTransitionExitTime(f32),
+    /// The blend duration of a transition is not positive
+    InvalidTransitionBlendDuration(f32),
+    /// The state does not exist
+    InvalidState(K),
+    /// The progress to play a state at is outside [0.0, 1.0]
+    InvalidStateProgress(f32),
+    /// The tag of a transition start state is not declared by any state
+    InvalidTransitionStartTag(String),
+    /// The weights of a random transition end state are all zero
+    InvalidTransitionEndWeights,
 }
 
 /// A state machine's current state
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CurrentState<K> {
     /// The current state key
     pub key: K,
@@ -766,29 +2086,299 @@ pub struct CurrentState<K> {
     pub duration: f32,
     /// The current state elapsed time
     pub elapsed: f32,
-    /// Whether the current state repeats
-    pub repeat: bool,
+    /// The current state playback mode
+    pub playback: Playback,
+    /// The current state playback speed, including the state machine speed
+    pub speed: f32,
+    /// The number of completed loops of the current state
+    pub loops: u32,
+    /// The direction the current state is currently played in
+    pub direction: PlaybackDirection,
+    /// The active parent states of the current state, outermost first
+    pub parents: Vec<K>,
 }
 
 impl<K> CurrentState<K> {
-    /// Returns the current state's progress [0.0, 1.0)
+    /// Returns the full active path, from the outermost parent state to the current state
+    pub fn path(&self) -> impl Iterator<Item = &K> {
+        self.parents.iter().chain([&self.key])
+    }
+
+    /// Advances the elapsed time by the playback speed, stopping at the end of the state or of a
+    /// loop of a repeating state and returning the time left over past it, if any.
+    ///
+    /// `resumed` is whether the time is left over from a loop the previous advance stopped at.
+    pub(crate) fn advance(
+        &mut self,
+        delta_time: f32,
+        resumed: bool,
+        steps: &mut Vec<Step<K>>,
+    ) -> Option<f32>
+    where
+        K: Clone,
+    {
+        let rate = self.speed.abs();
+        if delta_time * rate == 0.0 || self.completed() {
+            return None;
+        }
+
+        // Loops start at the end the state is played from
+        let start = if self.speed < 0.0 { self.duration } else { 0.0 };
+
+        // Ping-pong loops bounce off the far end and complete back at the start
+        let ping_pong = matches!(
+            self.playback,
+            Playback::PingPong | Playback::PingPongCount(_)
+        );
+
+        let mut remaining = delta_time * rate;
+        let mut from_swept = resumed && ping_pong;
+        loop {
+            let (elapsed, boundary, overflow) = match self.direction {
+                PlaybackDirection::Forward => {
+                    let elapsed = self.elapsed + remaining;
+                    (elapsed, self.duration, elapsed - self.duration)
+                }
+                PlaybackDirection::Backward => {
+                    let elapsed = self.elapsed - remaining;
+                    (elapsed, 0.0, -elapsed)
+                }
+            };
+            if overflow < 0.0 {
+                self.sweep(steps, elapsed, from_swept, false);
+                self.elapsed = elapsed;
+                return None;
+            }
+            remaining = overflow;
+
+            let completed = self
+                .playback
+                .loops()
+                .is_some_and(|x| self.loops + 1 >= x && (!ping_pong || boundary == start));
+
+            // A bounce the update stops at is swept by the next update instead
+            let to_swept = !ping_pong || completed || remaining > 0.0;
+            self.sweep(steps, boundary, from_swept, to_swept);
+            self.elapsed = boundary;
+            from_swept = ping_pong;
+
+            if ping_pong && boundary != start {
+                self.direction = self.direction.reversed();
+                continue;
+            }
+
+            self.loops += 1;
+            if completed {
+                steps.push(Step::Event(StateMachineEvent::Finished(self.key.clone())));
+                return match self.playback {
+                    Playback::Clamp => None,
+                    _ => Some(remaining / rate),
+                };
+            }
+
+            steps.push(Step::Event(StateMachineEvent::Looped(self.key.clone())));
+            if ping_pong {
+                self.direction = self.direction.reversed();
+            } else {
+                self.elapsed = start;
+            }
+
+            // Repeating states end on every loop, so their transitions are checked before the next
+            if self.playback.loops().is_none() {
+                return Some(remaining / rate);
+            }
+        }
+    }
+
+    /// Skips the whole loops of a repeating state in the time left over past the end of a loop,
+    /// recording them as a single loop, and returns the time left over past them
+    pub(crate) fn skip_loops(&mut self, delta_time: f32, steps: &mut Vec<Step<K>>) -> f32
+    where
+        K: Clone,
+    {
+        let rate = self.speed.abs();
+        let ping_pong = self.playback == Playback::PingPong;
+        let period = if ping_pong {
+            2.0 * self.duration
+        } else {
+            self.duration
+        };
+        let loops = (delta_time * rate / period).floor();
+        if self.playback.loops().is_some() || self.completed() || loops < 1.0 {
+            return delta_time;
+        }
+
+        // The state is at the start of a loop, so the skipped loops cross all of it
+        let start = self.elapsed;
+        let end = self.duration - start;
+        self.sweep(steps, end, ping_pong, true);
+        if ping_pong {
+            self.elapsed = end;
+            self.sweep(steps, start, true, true);
+            self.elapsed = start;
+        }
+        self.loops = self.loops.saturating_add(loops as u32);
+        steps.push(Step::Event(StateMachineEvent::Looped(self.key.clone())));
+
+        (delta_time * rate) % period / rate
+    }
+
+    /// Records the stretch from the elapsed time to another elapsed time
+    fn sweep(&self, steps: &mut Vec<Step<K>>, elapsed: f32, from_swept: bool, to_swept: bool)
+    where
+        K: Clone,
+    {
+        steps.push(Step::Sweep(Sweep {
+            key: self.key.clone(),
+            from: self.progress(),
+            to: elapsed / self.duration,
+            from_swept,
+            to_swept,
+        }));
+    }
+
+    /// Returns the current state's progress [0.0, 1.0]
     pub fn progress(&self) -> f32 {
         self.elapsed / self.duration
     }
 
-    /// Returns whether the current state is finished
+    /// Returns the played fraction of the current loop direction [0.0, 1.0]
+    pub(crate) fn played(&self) -> f32 {
+        match self.direction {
+            PlaybackDirection::Forward => self.progress(),
+            PlaybackDirection::Backward => 1.0 - self.progress(),
+        }
+    }
+
+    /// Returns whether the current state is finished, that is at the end it is played towards
     pub fn finished(&self) -> bool {
-        self.elapsed >= self.duration
+        match self.direction {
+            PlaybackDirection::Forward => self.elapsed >= self.duration,
+            PlaybackDirection::Backward => self.elapsed <= 0.0,
+        }
+    }
+
+    /// Returns whether the current state has completed all of its loops
+    fn completed(&self) -> bool {
+        self.duration <= 0.0 || self.playback.loops().is_some_and(|x| self.loops >= x)
+    }
+
+    /// Returns whether the current state has completed and triggers [`TransitionTrigger::End`]
+    pub(crate) fn ended(&self) -> bool {
+        self.playback != Playback::Clamp && self.completed()
+    }
+}
+
+/// A crossfade from an outgoing state into the current state
+#[derive(Clone, PartialEq, Debug)]
+pub struct Blend<K> {
+    /// The outgoing state
+    pub from: CurrentState<K>,
+    /// The crossfade duration
+    pub duration: f32,
+    /// The crossfade elapsed time
+    pub elapsed: f32,
+}
+
+impl<K> Blend<K> {
+    /// Returns the weight of the outgoing state (0.0, 1.0]
+    pub fn weight(&self) -> f32 {
+        1.0 - self.elapsed / self.duration
+    }
+}
+
+/// A state's playback mode
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Playback {
+    /// Play once, then trigger [`TransitionTrigger::End`] and hold on the last frame
+    Once,
+    /// Repeat forever, triggering [`TransitionTrigger::End`] at the end of every loop
+    Loop,
+    /// Repeat a number of times, then trigger [`TransitionTrigger::End`] and hold on the last
+    /// frame
+    LoopCount(u32),
+    /// Play forwards and backwards forever, triggering [`TransitionTrigger::End`] every time the
+    /// start is reached again
+    PingPong,
+    /// Play forwards and backwards a number of times, then trigger [`TransitionTrigger::End`]
+    /// and hold on the first frame
+    PingPongCount(u32),
+    /// Play once and hold on the last frame forever, without ever triggering
+    /// [`TransitionTrigger::End`]
+    Clamp,
+}
+
+impl Playback {
+    /// Returns the number of loops played, or [`None`] if the state repeats forever
+    fn loops(&self) -> Option<u32> {
+        match self {
+            Playback::Once | Playback::Clamp => Some(1),
+            Playback::LoopCount(loops) | Playback::PingPongCount(loops) => Some(*loops),
+            Playback::Loop | Playback::PingPong => None,
+        }
+    }
+}
+
+/// The direction a state is played in
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PlaybackDirection {
+    /// From the first frame to the last frame
+    Forward,
+    /// From the last frame to the first frame
+    Backward,
+}
+
+impl PlaybackDirection {
+    fn reversed(self) -> Self {
+        match self {
+            PlaybackDirection::Forward => PlaybackDirection::Backward,
+            PlaybackDirection::Backward => PlaybackDirection::Forward,
+        }
     }
 }
 
 /// A state
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct State {
     /// The state duration
     pub duration: f32,
-    /// Whether the state repeats
-    pub repeat: bool,
+    /// The playback mode
+    pub playback: Playback,
+    /// The playback speed multiplier, negative to play backwards
+    #[cfg_attr(feature = "serde", serde(default = "default_speed"))]
+    pub speed: f32,
+    /// The tags transitions may start in, see [`TransitionStartState::Tag`]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tags: Vec<String>,
+}
+
+#[cfg(feature = "serde")]
+fn default_speed() -> f32 {
+    1.0
+}
+
+impl State {
+    /// Creates a new [`State`] played at normal speed, either once or repeating forever
+    pub fn new(duration: f32, repeat: bool) -> Self {
+        let playback = match repeat {
+            true => Playback::Loop,
+            false => Playback::Once,
+        };
+        Self::with_playback(duration, playback)
+    }
+
+    /// Creates a new [`State`] with a playback mode, played at normal speed
+    pub fn with_playback(duration: f32, playback: Playback) -> Self {
+        Self {
+            duration,
+            playback,
+            speed: 1.0,
+            tags: Vec::new(),
+        }
+    }
 }
 
 /// A transition
@@ -799,39 +2389,376 @@ pub struct Transition<K, V> {
     /// The end state
     pub end_state: TransitionEndState<K>,
     /// The trigger
-    pub trigger: TransitionTrigger<V>,
+    pub trigger: TransitionTrigger<K, V>,
+    /// The minimum progress [0.0, 1.0] of the start state before the transition may fire.
+    ///
+    /// A triggered transition that has not reached its exit time waits until it does,
+    /// or until the end of the current loop.
+    pub exit_time: Option<f32>,
+    /// The duration of the crossfade from the start state into the end state.
+    ///
+    /// During the crossfade the start state keeps playing, see [`StateMachine::blend`].
+    pub blend_duration: Option<f32>,
+    /// Whether the transition may end in the current state or one of its parent states,
+    /// re-entering and restarting it.
+    ///
+    /// A conditional self-transition only fires again once its condition has been false.
+    pub self_transition: bool,
+    /// The priority of the transition over other transitions that can fire at the same time.
+    ///
+    /// When several transitions can fire, the one that fires is chosen by, in order:
+    ///
+    /// 1. the highest priority,
+    /// 2. the innermost nested state machine,
+    /// 3. a specific start state ([`TransitionStartState::Node`], [`TransitionStartState::AnyOf`]
+    ///    or [`TransitionStartState::Tag`]) over any state ([`TransitionStartState::Any`] or
+    ///    [`TransitionStartState::AnyExcept`]),
+    /// 4. the order the transitions were declared in.
+    ///
+    /// See [`StateMachine::set_report_ambiguities`] to find transitions that compete.
+    pub priority: i32,
+}
+
+impl<K, V> Transition<K, V> {
+    /// Creates a new [`Transition`] that fires as soon as it is triggered
+    pub fn new(
+        start_state: TransitionStartState<K>,
+        end_state: TransitionEndState<K>,
+        trigger: TransitionTrigger<K, V>,
+    ) -> Self {
+        Self {
+            start_state,
+            end_state,
+            trigger,
+            exit_time: None,
+            blend_duration: None,
+            self_transition: false,
+            priority: 0,
+        }
+    }
 }
 
 /// A transition start state
 #[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TransitionStartState<K> {
-    /// Any state
+    /// Any state of the state machine the transition belongs to
     Any,
-    /// A specific state
+    /// A specific state, or any state of a specific sub state machine
     Node(K),
+    /// Any of the given states of the state machine the transition belongs to
+    AnyOf(Vec<K>),
+    /// Any state of the state machine the transition belongs to, except the given states
+    AnyExcept(Vec<K>),
+    /// Any state with the given tag, see [`State::tags`]
+    Tag(String),
 }
 
 /// A transition end state
 #[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TransitionEndState<K> {
     /// A specific state
     Node(K),
+    /// The state that was active before the current state, see
+    /// [`StateMachine::set_history_depth`].
+    ///
+    /// The transition does not fire when there is no previous state.
+    Previous {
+        /// Whether to resume the previous state where it was left instead of restarting it
+        resume: bool,
+    },
+    /// One of the given states, picked at random by weight, see [`StateMachine::set_seed`].
+    ///
+    /// Picking the current state keeps playing it instead of transitioning.
+    Random(Vec<(K, u32)>),
 }
 
 /// A trigger
 #[derive(Clone)]
-pub enum TransitionTrigger<V> {
-    /// A condition
-    Condition(Box<fn(&V) -> bool>),
-    /// End
+pub enum TransitionTrigger<K, V> {
+    /// A condition, which may capture its environment.
+    ///
+    /// It is checked on parameter updates, and on time updates under
+    /// [`EvaluationPolicy::EveryUpdate`].
+    Condition(Arc<dyn Fn(&V) -> bool + Send + Sync>),
+    /// A condition that also sees the current state and the time spent in it.
+    ///
+    /// Unlike [`TransitionTrigger::Condition`] it is checked on time updates under any
+    /// [`EvaluationPolicy`].
+    Context(ContextFn<K, V>),
+    /// A declarative comparison of a named parameter, checked like
+    /// [`TransitionTrigger::Condition`]
+    Compare(Comparison<V>),
+    /// A one-shot event, see [`StateMachine::fire`]
+    Event(String),
+    /// The end of the start state, checked on time updates
     End,
+    /// All of the triggers
+    All(Vec<TransitionTrigger<K, V>>),
+    /// Any of the triggers
+    Any(Vec<TransitionTrigger<K, V>>),
+    /// The opposite of the trigger
+    Not(Box<TransitionTrigger<K, V>>),
+    /// A minimum time in seconds since the start state was entered, regardless of its speed
+    After(f32),
+    /// A minimum number of completed loops of the start state
+    Loops(u32),
+    /// The frame at the index of the start state being reached in the direction it plays.
+    ///
+    /// Frames are only known to an [`Animator`], so it never triggers a bare [`StateMachine`].
+    FrameReached(usize),
 }
 
-impl<V> Debug for TransitionTrigger<V> {
+type ContextFn<K, V> = Arc<dyn Fn(&ConditionContext<K, V>) -> bool + Send + Sync>;
+
+impl<K, V> Debug for TransitionTrigger<K, V> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             TransitionTrigger::Condition(_) => write!(f, "Condition"),
+            TransitionTrigger::Context(_) => write!(f, "Context"),
+            TransitionTrigger::Compare(comparison) => write!(f, "Compare({:?})", comparison),
+            TransitionTrigger::Event(event) => write!(f, "Event({:?})", event),
             TransitionTrigger::End => write!(f, "End"),
+            TransitionTrigger::All(triggers) => write!(f, "All({:?})", triggers),
+            TransitionTrigger::Any(triggers) => write!(f, "Any({:?})", triggers),
+            TransitionTrigger::Not(trigger) => write!(f, "Not({:?})", trigger),
+            TransitionTrigger::After(seconds) => write!(f, "After({:?})", seconds),
+            TransitionTrigger::Loops(loops) => write!(f, "Loops({:?})", loops),
+            TransitionTrigger::FrameReached(index) => write!(f, "FrameReached({:?})", index),
+        }
+    }
+}
+
+impl<K, V> TransitionTrigger<K, V> {
+    /// Returns whether any of the triggers it is made of matches, looking through `All`, `Any`
+    /// and `Not`
+    fn contains(&self, matches: &impl Fn(&Self) -> bool) -> bool {
+        match self {
+            TransitionTrigger::All(triggers) | TransitionTrigger::Any(triggers) => {
+                triggers.iter().any(|x| x.contains(matches))
+            }
+            TransitionTrigger::Not(trigger) => trigger.contains(matches),
+            _ => matches(self),
         }
     }
+
+    /// Returns the triggers it is made of, leaving out negated triggers
+    fn positive(&self) -> Vec<&Self> {
+        match self {
+            TransitionTrigger::All(triggers) | TransitionTrigger::Any(triggers) => {
+                triggers.iter().flat_map(|x| x.positive()).collect()
+            }
+            TransitionTrigger::Not(_) => Vec::new(),
+            _ => vec![self],
+        }
+    }
+}
+
+/// A comparison of a named parameter against a value, see [`Parameters`]
+#[derive(Clone)]
+pub struct Comparison<V> {
+    /// The name of the parameter
+    pub parameter: String,
+    /// How the parameter is compared
+    pub operator: ComparisonOperator,
+    /// The value the parameter is compared against
+    pub value: ParameterValue,
+    get: fn(&V, &str) -> Option<ParameterValue>,
+    reset_trigger: fn(&mut V, &str),
+}
+
+impl<V> Comparison<V>
+where
+    V: Parameters,
+{
+    /// Creates a new [`Comparison`], such as `speed > 0.1`
+    pub fn new(
+        parameter: impl Into<String>,
+        operator: ComparisonOperator,
+        value: impl Into<ParameterValue>,
+    ) -> Self {
+        Self {
+            parameter: parameter.into(),
+            operator,
+            value: value.into(),
+            get: V::parameter,
+            reset_trigger: V::reset_trigger,
+        }
+    }
+
+    /// Creates a new [`Comparison`] that holds while a [`ParameterValue::Trigger`] is set
+    pub fn triggered(parameter: impl Into<String>) -> Self {
+        Self::new(
+            parameter,
+            ComparisonOperator::Equal,
+            ParameterValue::Trigger(true),
+        )
+    }
+}
+
+impl<V> Comparison<V> {
+    /// Returns whether the comparison holds, which it does not for missing parameters or
+    /// values of different types
+    fn matches(&self, parameters: &V) -> bool {
+        (self.get)(parameters, &self.parameter)
+            .and_then(|x| x.compare(&self.value))
+            .is_some_and(|x| self.operator.matches(x))
+    }
+}
+
+impl<V> Debug for Comparison<V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Comparison")
+            .field("parameter", &self.parameter)
+            .field("operator", &self.operator)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+/// How a [`Comparison`] compares a parameter against its value
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ComparisonOperator {
+    /// `==`
+    Equal,
+    /// `!=`
+    NotEqual,
+    /// `<`
+    Less,
+    /// `<=`
+    LessOrEqual,
+    /// `>`
+    Greater,
+    /// `>=`
+    GreaterOrEqual,
+}
+
+impl ComparisonOperator {
+    fn matches(self, ordering: Ordering) -> bool {
+        match self {
+            ComparisonOperator::Equal => ordering.is_eq(),
+            ComparisonOperator::NotEqual => ordering.is_ne(),
+            ComparisonOperator::Less => ordering.is_lt(),
+            ComparisonOperator::LessOrEqual => ordering.is_le(),
+            ComparisonOperator::Greater => ordering.is_gt(),
+            ComparisonOperator::GreaterOrEqual => ordering.is_ge(),
+        }
+    }
+}
+
+/// The value of a named parameter
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ParameterValue {
+    /// A number, comparable with [`ParameterValue::Int`]
+    Float(f32),
+    /// A whole number, comparable with [`ParameterValue::Float`]
+    Int(i32),
+    /// A flag, comparable with [`ParameterValue::Trigger`]
+    Bool(bool),
+    /// A flag that is reset once a [`TransitionTrigger::Compare`] transition it caused fires
+    Trigger(bool),
+}
+
+impl ParameterValue {
+    fn compare(&self, other: &Self) -> Option<Ordering> {
+        match (*self, *other) {
+            (ParameterValue::Float(a), ParameterValue::Float(b)) => a.partial_cmp(&b),
+            (ParameterValue::Float(a), ParameterValue::Int(b)) => a.partial_cmp(&(b as f32)),
+            (ParameterValue::Int(a), ParameterValue::Float(b)) => (a as f32).partial_cmp(&b),
+            (ParameterValue::Int(a), ParameterValue::Int(b)) => Some(a.cmp(&b)),
+            (
+                ParameterValue::Bool(a) | ParameterValue::Trigger(a),
+                ParameterValue::Bool(b) | ParameterValue::Trigger(b),
+            ) => Some(a.cmp(&b)),
+            _ => None,
+        }
+    }
+}
+
+impl From<f32> for ParameterValue {
+    fn from(value: f32) -> Self {
+        ParameterValue::Float(value)
+    }
+}
+
+impl From<i32> for ParameterValue {
+    fn from(value: i32) -> Self {
+        ParameterValue::Int(value)
+    }
+}
+
+impl From<bool> for ParameterValue {
+    fn from(value: bool) -> Self {
+        ParameterValue::Bool(value)
+    }
+}
+
+/// Parameters that can be looked up by name, for [`TransitionTrigger::Compare`]
+pub trait Parameters {
+    /// Returns the value of a parameter, if there is one with the name
+    fn parameter(&self, name: &str) -> Option<ParameterValue>;
+
+    /// Resets a parameter if it is a [`ParameterValue::Trigger`], called for the parameters
+    /// compared by a transition that fires
+    fn reset_trigger(&mut self, name: &str) {
+        let _ = name;
+    }
+}
+
+/// Named parameters that are defined at runtime, see [`Parameters`]
+#[derive(Clone, Default, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParameterMap {
+    values: HashMap<String, ParameterValue>,
+}
+
+impl ParameterMap {
+    /// Creates a new, empty [`ParameterMap`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the value of a parameter, if there is one with the name
+    pub fn get(&self, name: &str) -> Option<ParameterValue> {
+        self.values.get(name).copied()
+    }
+
+    /// Sets the value of a parameter
+    pub fn set(&mut self, name: impl Into<String>, value: impl Into<ParameterValue>) {
+        self.values.insert(name.into(), value.into());
+    }
+
+    /// Sets a [`ParameterValue::Trigger`], which stays set until a transition comparing it fires
+    pub fn trigger(&mut self, name: impl Into<String>) {
+        self.values
+            .insert(name.into(), ParameterValue::Trigger(true));
+    }
+}
+
+impl Parameters for ParameterMap {
+    fn parameter(&self, name: &str) -> Option<ParameterValue> {
+        self.get(name)
+    }
+
+    fn reset_trigger(&mut self, name: &str) {
+        if let Some(ParameterValue::Trigger(value)) = self.values.get_mut(name) {
+            *value = false;
+        }
+    }
+}
+
+/// What a [`TransitionTrigger::Context`] condition sees
+#[derive(Debug)]
+pub struct ConditionContext<'a, K, V> {
+    /// The parameters
+    pub parameters: &'a V,
+    /// The current state
+    pub state: &'a CurrentState<K>,
+    /// The state that was active before the current state, if any
+    pub previous_state: Option<&'a K>,
+    /// The time since the current state was entered, regardless of its speed
+    pub time_in_state: f32,
 }
@@ -11,13 +11,7 @@ fn clone() {
     let animator = Animator::new(
         StateMachine::new(
             "idle".to_string(),
-            HashMap::from([(
-                "idle".to_string(),
-                State {
-                    duration: 0.5,
-                    repeat: true,
-                },
-            )]),
+            HashMap::from([("idle".to_string(), State::new(0.5, true))]),
             vec![],
             Params {
                 speed: 1.0,
@@ -53,13 +47,7 @@ fn debug() {
     let animator = Animator::new(
         StateMachine::new(
             "idle".to_string(),
-            HashMap::from([(
-                "idle".to_string(),
-                State {
-                    duration: 0.5,
-                    repeat: true,
-                },
-            )]),
+            HashMap::from([("idle".to_string(), State::new(0.5, true))]),
             vec![],
             Params {
                 speed: 1.0,
@@ -89,6 +77,6 @@ fn debug() {
 
     assert_eq!(
         format!("{:?}", animator),
-        "Animator { state_machine: StateMachine { current_state: CurrentState { key: \"idle\", duration: 0.5, elapsed: 0.0, repeat: true }, states: {\"idle\": State { duration: 0.5, repeat: true }}, transitions: [], parameters: Params { speed: 1.0, jump: false } }, state_frames: {\"idle\": [Frame { progress: 0.0, value: 0 }, Frame { progress: 0.33, value: 1 }, Frame { progress: 0.67, value: 2 }]} }"
+        "Animator { state_machine: StateMachine { current_state: CurrentState { key: \"idle\", duration: 0.5, elapsed: 0.0, playback: Loop, speed: 1.0, loops: 0, direction: Forward, parents: [] }, states: {\"idle\": State { duration: 0.5, playback: Loop, speed: 1.0, tags: [] }}, transitions: [], parameters: Params { speed: 1.0, jump: false }, events: [], event_lifetime: 1, speed: 1.0, blend: None, scopes: [], parents: {}, entry_states: {}, latched: {}, history: [], history_depth: 1, rng: 2685821657736338717, report_ambiguities: false, previous_state: None, time_in_state: 0.0, evaluation_policy: EveryUpdate, frame_progress: {\"idle\": [0.0, 0.33, 0.67]} }, state_frames: {\"idle\": [Frame { progress: 0.0, value: 0 }, Frame { progress: 0.33, value: 1 }, Frame { progress: 0.67, value: 2 }]}, state_events: {}, blend_spaces: {}, layers: [] }"
     );
 }
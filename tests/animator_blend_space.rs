@@ -0,0 +1,215 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Animation {
+    Idle,
+    Move,
+    Look,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {
+    pub speed: f32,
+    pub direction: (f32, f32),
+}
+
+fn frames(values: [u8; 2]) -> Vec<Frame<u8>> {
+    vec![
+        Frame {
+            progress: 0.0,
+            value: values[0],
+        },
+        Frame {
+            progress: 0.5,
+            value: values[1],
+        },
+    ]
+}
+
+fn create_sm(starting_state: Animation, params: Params) -> StateMachine<Animation, Params> {
+    StateMachine::new(
+        starting_state,
+        HashMap::from([
+            (Animation::Idle, State::new(0.5, true)),
+            (Animation::Move, State::new(1.0, true)),
+            (Animation::Look, State::new(1.0, true)),
+        ]),
+        vec![Transition::new(
+            TransitionStartState::Node(Animation::Idle),
+            TransitionEndState::Node(Animation::Move),
+            TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed > 0.0)),
+        )],
+        params,
+    )
+    .unwrap()
+}
+
+fn create_animator(starting_state: Animation, params: Params) -> Animator<Animation, Params, u8> {
+    Animator::with_blend_spaces(
+        create_sm(starting_state, params),
+        HashMap::from([(Animation::Idle, frames([0, 1]))]),
+        HashMap::from([
+            (
+                Animation::Move,
+                BlendSpace {
+                    parameter: BlendParameter::Scalar(Arc::new(|x: &Params| x.speed)),
+                    clips: vec![
+                        BlendClip {
+                            position: (1.0, 0.0),
+                            duration: 1.0,
+                            frames: frames([10, 11]),
+                        },
+                        BlendClip {
+                            position: (3.0, 0.0),
+                            duration: 0.5,
+                            frames: frames([20, 21]),
+                        },
+                    ],
+                },
+            ),
+            (
+                Animation::Look,
+                BlendSpace {
+                    parameter: BlendParameter::Vector(Arc::new(|x: &Params| x.direction)),
+                    clips: vec![
+                        BlendClip {
+                            position: (1.0, 0.0),
+                            duration: 1.0,
+                            frames: frames([30, 31]),
+                        },
+                        BlendClip {
+                            position: (0.0, 1.0),
+                            duration: 1.0,
+                            frames: frames([40, 41]),
+                        },
+                    ],
+                },
+            ),
+        ]),
+    )
+    .unwrap()
+}
+
+#[test]
+fn blend_space_scalar() {
+    let mut animator = create_animator(
+        Animation::Idle,
+        Params {
+            speed: 0.0,
+            direction: (1.0, 0.0),
+        },
+    );
+
+    animator.update_parameters(&|x| {
+        x.speed = 1.2;
+    });
+    assert_eq!(animator.state().key, Animation::Move);
+    assert_eq!(animator.state().duration, 1.0);
+    assert_eq!(animator.frame(), &10);
+
+    animator.update_parameters(&|x| {
+        x.speed = 2.5;
+    });
+    assert_eq!(animator.state().duration, 0.5);
+    assert_eq!(animator.frame(), &20);
+}
+
+#[test]
+fn blend_space_keeps_progress() {
+    let mut animator = create_animator(
+        Animation::Move,
+        Params {
+            speed: 1.0,
+            direction: (1.0, 0.0),
+        },
+    );
+
+    animator.update(0.5);
+    assert_eq!(animator.frame(), &11);
+
+    animator.update_parameters(&|x| {
+        x.speed = 3.0;
+    });
+    assert_eq!(animator.state().progress(), 0.5);
+    assert_eq!(animator.state().elapsed, 0.25);
+    assert_eq!(animator.frame(), &21);
+}
+
+#[test]
+fn blend_space_vector() {
+    let mut animator = create_animator(
+        Animation::Look,
+        Params {
+            speed: 0.0,
+            direction: (0.9, 0.1),
+        },
+    );
+
+    assert_eq!(animator.frame(), &30);
+
+    animator.update_parameters(&|x| {
+        x.direction = (-0.2, 0.8);
+    });
+    assert_eq!(animator.frame(), &40);
+}
+
+#[test]
+fn blend_space_empty() {
+    let err = Animator::with_blend_spaces(
+        create_sm(
+            Animation::Idle,
+            Params {
+                speed: 0.0,
+                direction: (1.0, 0.0),
+            },
+        ),
+        HashMap::from([
+            (Animation::Idle, frames([0, 1])),
+            (Animation::Look, frames([0, 1])),
+        ]),
+        HashMap::from([(
+            Animation::Move,
+            BlendSpace {
+                parameter: BlendParameter::Scalar(Arc::new(|x: &Params| x.speed)),
+                clips: vec![],
+            },
+        )]),
+    )
+    .expect_err("empty blend space");
+
+    assert_eq!(err, AnimatorError::EmptyBlendSpace(Animation::Move));
+}
+
+#[test]
+fn blend_space_invalid_clip_duration() {
+    let err = Animator::with_blend_spaces(
+        create_sm(
+            Animation::Idle,
+            Params {
+                speed: 0.0,
+                direction: (1.0, 0.0),
+            },
+        ),
+        HashMap::from([
+            (Animation::Idle, frames([0, 1])),
+            (Animation::Look, frames([0, 1])),
+        ]),
+        HashMap::from([(
+            Animation::Move,
+            BlendSpace {
+                parameter: BlendParameter::Scalar(Arc::new(|x: &Params| x.speed)),
+                clips: vec![BlendClip {
+                    position: (1.0, 0.0),
+                    duration: 0.0,
+                    frames: frames([10, 11]),
+                }],
+            },
+        )]),
+    )
+    .expect_err("invalid blend clip duration");
+
+    assert_eq!(
+        err,
+        AnimatorError::InvalidBlendClipDuration(Animation::Move, 0.0)
+    );
+}
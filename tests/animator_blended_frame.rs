@@ -0,0 +1,89 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {
+    pub speed: f32,
+}
+
+fn create_animator() -> Animator<String, Params, u8> {
+    Animator::new(
+        StateMachine::new(
+            "idle".to_string(),
+            HashMap::from([
+                ("idle".to_string(), State::new(0.5, true)),
+                ("walk".to_string(), State::new(1.0, true)),
+            ]),
+            vec![Transition {
+                blend_duration: Some(0.4),
+                ..Transition::new(
+                    TransitionStartState::Node("idle".to_string()),
+                    TransitionEndState::Node("walk".to_string()),
+                    TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed > 0.0)),
+                )
+            }],
+            Params { speed: 0.0 },
+        )
+        .unwrap(),
+        HashMap::from([
+            (
+                "idle".to_string(),
+                vec![
+                    Frame {
+                        progress: 0.0,
+                        value: 0,
+                    },
+                    Frame {
+                        progress: 0.5,
+                        value: 1,
+                    },
+                ],
+            ),
+            (
+                "walk".to_string(),
+                vec![
+                    Frame {
+                        progress: 0.0,
+                        value: 10,
+                    },
+                    Frame {
+                        progress: 0.5,
+                        value: 11,
+                    },
+                ],
+            ),
+        ]),
+    )
+    .unwrap()
+}
+
+#[test]
+fn blended_frame_without_blend() {
+    let animator = create_animator();
+
+    assert_eq!(animator.blended_frame(), (&0, None));
+}
+
+#[test]
+fn blended_frame_during_blend() {
+    let mut animator = create_animator();
+
+    animator.update_parameters(&|x| {
+        x.speed = 1.0;
+    });
+    assert_eq!(animator.blended_frame(), (&10, Some((&0, 1.0))));
+
+    animator.update(0.3);
+    assert_eq!(animator.blended_frame(), (&10, Some((&1, 0.25))));
+}
+
+#[test]
+fn blended_frame_after_blend() {
+    let mut animator = create_animator();
+
+    animator.update_parameters(&|x| {
+        x.speed = 1.0;
+    });
+    animator.update(0.5);
+
+    assert_eq!(animator.blended_frame(), (&11, None));
+}
@@ -0,0 +1,276 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {}
+
+fn frames() -> Vec<Frame<u8>> {
+    vec![Frame {
+        progress: 0.0,
+        value: 0,
+    }]
+}
+
+fn create_animator(idle: Playback) -> Animator<String, Params, u8, &'static str> {
+    Animator::new(
+        StateMachine::new(
+            "land".to_string(),
+            HashMap::from([
+                ("land".to_string(), State::new(1.0, false)),
+                ("idle".to_string(), State::with_playback(1.0, idle)),
+            ]),
+            vec![Transition::new(
+                TransitionStartState::Node("land".to_string()),
+                TransitionEndState::Node("idle".to_string()),
+                TransitionTrigger::End,
+            )],
+            Params {},
+        )
+        .unwrap(),
+        HashMap::from([
+            ("land".to_string(), frames()),
+            ("idle".to_string(), frames()),
+        ]),
+    )
+    .unwrap()
+    .with_events(HashMap::from([
+        (
+            "land".to_string(),
+            vec![
+                FrameEvent {
+                    progress: 0.0,
+                    event: "dust",
+                },
+                FrameEvent {
+                    progress: 0.5,
+                    event: "thud",
+                },
+            ],
+        ),
+        (
+            "idle".to_string(),
+            vec![
+                FrameEvent {
+                    progress: 0.25,
+                    event: "left",
+                },
+                FrameEvent {
+                    progress: 0.75,
+                    event: "right",
+                },
+            ],
+        ),
+    ]))
+    .unwrap()
+}
+
+fn frame_events(events: Vec<AnimatorEvent<String, &'static str>>) -> Vec<&'static str> {
+    events
+        .into_iter()
+        .filter_map(|x| match x {
+            AnimatorEvent::Frame(event) => Some(event),
+            AnimatorEvent::State(_) | AnimatorEvent::Layer(..) => None,
+        })
+        .collect()
+}
+
+#[test]
+fn animator_events() {
+    let mut animator = create_animator(Playback::Loop);
+
+    assert_eq!(frame_events(animator.update(0.25)), vec!["dust"]);
+    assert_eq!(frame_events(animator.update(0.25)), Vec::<&str>::new());
+    assert_eq!(frame_events(animator.update(0.25)), vec!["thud"]);
+}
+
+#[test]
+fn animator_events_across_transitions() {
+    let mut animator = create_animator(Playback::Loop);
+
+    assert_eq!(
+        frame_events(animator.update(1.5)),
+        vec!["dust", "thud", "left"]
+    );
+    assert_eq!(animator.state().key, "idle");
+}
+
+#[test]
+fn animator_events_wrap() {
+    let mut animator = create_animator(Playback::Loop);
+    animator.update(1.0);
+
+    assert_eq!(
+        frame_events(animator.update(2.5)),
+        vec!["left", "right", "left", "right", "left"]
+    );
+}
+
+#[test]
+fn animator_events_ping_pong() {
+    let mut animator = create_animator(Playback::PingPong);
+    animator.update(1.0);
+
+    assert_eq!(
+        frame_events(animator.update(1.5)),
+        vec!["left", "right", "right"]
+    );
+    assert_eq!(frame_events(animator.update(0.5)), vec!["left"]);
+}
+
+#[test]
+fn animator_events_reverse() {
+    let mut animator = create_animator(Playback::Loop);
+    animator.update(1.0);
+    animator.set_speed(-1.0);
+
+    // the idle was at its start, so it wraps around to its end
+    assert_eq!(frame_events(animator.update(0.875)), vec!["right", "left"]);
+}
+
+#[test]
+fn animator_events_unsorted() {
+    let animator: Animator<String, Params, u8> = Animator::new(
+        StateMachine::new(
+            "idle".to_string(),
+            HashMap::from([("idle".to_string(), State::new(1.0, true))]),
+            vec![],
+            Params {},
+        )
+        .unwrap(),
+        HashMap::from([("idle".to_string(), frames())]),
+    )
+    .unwrap();
+
+    let error = animator
+        .with_events(HashMap::from([(
+            "idle".to_string(),
+            vec![
+                FrameEvent {
+                    progress: 0.5,
+                    event: 0,
+                },
+                FrameEvent {
+                    progress: 0.25,
+                    event: 1,
+                },
+            ],
+        )]))
+        .expect_err("Expected error");
+
+    assert_eq!(
+        error,
+        AnimatorError::UnsortedStateEvents("idle".to_string())
+    );
+}
+
+#[test]
+fn animator_events_invalid_progress() {
+    let animator: Animator<String, Params, u8> = Animator::new(
+        StateMachine::new(
+            "idle".to_string(),
+            HashMap::from([("idle".to_string(), State::new(1.0, true))]),
+            vec![],
+            Params {},
+        )
+        .unwrap(),
+        HashMap::from([("idle".to_string(), frames())]),
+    )
+    .unwrap();
+
+    let error = animator
+        .with_events(HashMap::from([(
+            "idle".to_string(),
+            vec![FrameEvent {
+                progress: 1.5,
+                event: 0,
+            }],
+        )]))
+        .expect_err("Expected error");
+
+    assert_eq!(
+        error,
+        AnimatorError::InvalidStateEventProgress("idle".to_string(), 1.5)
+    );
+}
+
+#[test]
+fn animator_events_interleaved() {
+    let mut animator = create_animator(Playback::LoopCount(2));
+
+    assert_eq!(
+        animator.update(2.5),
+        vec![
+            AnimatorEvent::Frame("dust"),
+            AnimatorEvent::Frame("thud"),
+            AnimatorEvent::State(StateMachineEvent::Finished("land".to_string())),
+            AnimatorEvent::State(StateMachineEvent::Exited("land".to_string())),
+            AnimatorEvent::State(StateMachineEvent::TransitionFired {
+                from: "land".to_string(),
+                to: "idle".to_string(),
+                index: 0,
+            }),
+            AnimatorEvent::State(StateMachineEvent::Entered("idle".to_string())),
+            AnimatorEvent::Frame("left"),
+            AnimatorEvent::Frame("right"),
+            AnimatorEvent::State(StateMachineEvent::Looped("idle".to_string())),
+            AnimatorEvent::Frame("left"),
+        ]
+    );
+}
+
+#[test]
+fn animator_events_loop_end_transition() {
+    let mut animator: Animator<String, Params, u8, &'static str> = Animator::new(
+        StateMachine::new(
+            "x".to_string(),
+            HashMap::from([
+                ("x".to_string(), State::new(1.0, true)),
+                ("y".to_string(), State::new(2.0, true)),
+            ]),
+            vec![Transition::new(
+                TransitionStartState::Node("x".to_string()),
+                TransitionEndState::Node("y".to_string()),
+                TransitionTrigger::End,
+            )],
+            Params {},
+        )
+        .unwrap(),
+        HashMap::from([("x".to_string(), frames()), ("y".to_string(), frames())]),
+    )
+    .unwrap()
+    .with_events(HashMap::from([
+        (
+            "x".to_string(),
+            vec![FrameEvent {
+                progress: 0.5,
+                event: "x-mid",
+            }],
+        ),
+        (
+            "y".to_string(),
+            vec![FrameEvent {
+                progress: 0.5,
+                event: "y",
+            }],
+        ),
+    ]))
+    .unwrap();
+
+    // the first loop ends the state, so the rest of the update is played by the next state
+    assert_eq!(
+        animator.update(2.5),
+        vec![
+            AnimatorEvent::Frame("x-mid"),
+            AnimatorEvent::State(StateMachineEvent::Looped("x".to_string())),
+            AnimatorEvent::State(StateMachineEvent::Exited("x".to_string())),
+            AnimatorEvent::State(StateMachineEvent::TransitionFired {
+                from: "x".to_string(),
+                to: "y".to_string(),
+                index: 0,
+            }),
+            AnimatorEvent::State(StateMachineEvent::Entered("y".to_string())),
+            AnimatorEvent::Frame("y"),
+        ]
+    );
+    assert_eq!(animator.state().key, "y");
+    assert_eq!(animator.state().elapsed, 1.5);
+}
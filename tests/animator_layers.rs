@@ -0,0 +1,356 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Animation {
+    Idle,
+    Walk,
+    Rest,
+    Attack,
+    Hidden,
+    Flash,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {
+    pub speed: f32,
+    pub attack: bool,
+}
+
+fn frames(value: u8) -> Vec<Frame<u8>> {
+    vec![Frame {
+        progress: 0.0,
+        value,
+    }]
+}
+
+fn create_animator() -> Animator<Animation, Params, u8> {
+    let params = Params {
+        speed: 0.0,
+        attack: false,
+    };
+
+    let mut animator = Animator::new(
+        StateMachine::new(
+            Animation::Idle,
+            HashMap::from([
+                (Animation::Idle, State::new(0.5, true)),
+                (Animation::Walk, State::new(1.0, true)),
+            ]),
+            vec![Transition::new(
+                TransitionStartState::Node(Animation::Idle),
+                TransitionEndState::Node(Animation::Walk),
+                TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed > 0.0)),
+            )],
+            params.clone(),
+        )
+        .unwrap(),
+        HashMap::from([(Animation::Idle, frames(0)), (Animation::Walk, frames(1))]),
+    )
+    .unwrap();
+
+    animator
+        .add_layer(
+            StateMachine::new(
+                Animation::Rest,
+                HashMap::from([
+                    (Animation::Rest, State::new(0.5, true)),
+                    (Animation::Attack, State::new(0.5, false)),
+                ]),
+                vec![Transition::new(
+                    TransitionStartState::Node(Animation::Rest),
+                    TransitionEndState::Node(Animation::Attack),
+                    TransitionTrigger::Condition(Arc::new(|x: &Params| x.attack)),
+                )],
+                params.clone(),
+            )
+            .unwrap(),
+            HashMap::from([(Animation::Attack, frames(2))]),
+            LayerBlending::Override,
+        )
+        .unwrap();
+
+    animator
+        .add_layer(
+            StateMachine::new(
+                Animation::Hidden,
+                HashMap::from([
+                    (Animation::Hidden, State::new(0.5, true)),
+                    (Animation::Flash, State::new(0.25, false)),
+                ]),
+                vec![
+                    Transition::new(
+                        TransitionStartState::Node(Animation::Hidden),
+                        TransitionEndState::Node(Animation::Flash),
+                        TransitionTrigger::Event("hit".to_string()),
+                    ),
+                    Transition::new(
+                        TransitionStartState::Node(Animation::Flash),
+                        TransitionEndState::Node(Animation::Hidden),
+                        TransitionTrigger::End,
+                    ),
+                ],
+                params,
+            )
+            .unwrap(),
+            HashMap::from([(Animation::Flash, frames(3))]),
+            LayerBlending::Additive,
+        )
+        .unwrap();
+
+    animator
+}
+
+#[test]
+fn layers_hidden() {
+    let animator = create_animator();
+
+    assert_eq!(animator.frames(), vec![&0]);
+}
+
+#[test]
+fn layers_shared_parameters() {
+    let mut animator = create_animator();
+
+    animator.update_parameters(&|x| {
+        x.speed = 1.0;
+        x.attack = true;
+    });
+
+    assert_eq!(animator.state().key, Animation::Walk);
+    assert_eq!(animator.layer_state(0).unwrap().key, Animation::Attack);
+    assert_eq!(animator.layer_state(1).unwrap().key, Animation::Hidden);
+    assert_eq!(animator.layer_state(2), None);
+}
+
+#[test]
+fn layers_override() {
+    let mut animator = create_animator();
+
+    animator.update_parameters(&|x| {
+        x.attack = true;
+    });
+
+    assert_eq!(animator.frame(), &0);
+    assert_eq!(animator.frames(), vec![&2]);
+}
+
+#[test]
+fn layers_additive() {
+    let mut animator = create_animator();
+
+    animator.fire("hit");
+    assert_eq!(animator.frames(), vec![&0, &3]);
+
+    animator.update_parameters(&|x| {
+        x.attack = true;
+    });
+    assert_eq!(animator.frames(), vec![&2, &3]);
+
+    animator.update(0.25);
+    assert_eq!(animator.frames(), vec![&2]);
+}
+
+#[test]
+fn layers_invalid_state_frames() {
+    let mut animator = create_animator();
+
+    let err = animator
+        .add_layer(
+            StateMachine::new(
+                Animation::Hidden,
+                HashMap::from([(Animation::Hidden, State::new(0.5, true))]),
+                vec![],
+                Params {
+                    speed: 0.0,
+                    attack: false,
+                },
+            )
+            .unwrap(),
+            HashMap::from([(Animation::Hidden, vec![])]),
+            LayerBlending::Additive,
+        )
+        .expect_err("empty state frames");
+
+    assert_eq!(err, AnimatorError::EmptyStateFrames(Animation::Hidden));
+}
+
+#[test]
+fn layers_update_events() {
+    let mut animator = create_animator();
+    animator.fire("hit");
+
+    assert_eq!(
+        animator.update(0.25),
+        vec![
+            AnimatorEvent::Layer(1, StateMachineEvent::Finished(Animation::Flash)),
+            AnimatorEvent::Layer(1, StateMachineEvent::Exited(Animation::Flash)),
+            AnimatorEvent::Layer(
+                1,
+                StateMachineEvent::TransitionFired {
+                    from: Animation::Flash,
+                    to: Animation::Hidden,
+                    index: 1,
+                }
+            ),
+            AnimatorEvent::Layer(1, StateMachineEvent::Entered(Animation::Hidden)),
+        ]
+    );
+}
+
+#[test]
+fn layers_shared_trigger_reset() {
+    let hit = || TransitionTrigger::Compare(Comparison::triggered("hit"));
+    let mut animator: Animator<Animation, ParameterMap, u8> = Animator::new(
+        StateMachine::new(
+            Animation::Idle,
+            HashMap::from([
+                (Animation::Idle, State::new(0.5, true)),
+                (Animation::Walk, State::new(0.5, true)),
+            ]),
+            vec![Transition::new(
+                TransitionStartState::Node(Animation::Idle),
+                TransitionEndState::Node(Animation::Walk),
+                hit(),
+            )],
+            ParameterMap::new(),
+        )
+        .unwrap(),
+        HashMap::from([(Animation::Idle, frames(0)), (Animation::Walk, frames(1))]),
+    )
+    .unwrap();
+    animator
+        .add_layer(
+            StateMachine::new(
+                Animation::Rest,
+                HashMap::from([
+                    (Animation::Rest, State::new(0.5, false)),
+                    (Animation::Hidden, State::new(0.5, true)),
+                    (Animation::Flash, State::new(0.5, true)),
+                ]),
+                vec![
+                    Transition::new(
+                        TransitionStartState::Node(Animation::Rest),
+                        TransitionEndState::Node(Animation::Hidden),
+                        TransitionTrigger::End,
+                    ),
+                    Transition::new(
+                        TransitionStartState::Node(Animation::Hidden),
+                        TransitionEndState::Node(Animation::Flash),
+                        hit(),
+                    ),
+                ],
+                ParameterMap::new(),
+            )
+            .unwrap(),
+            HashMap::new(),
+            LayerBlending::Additive,
+        )
+        .unwrap();
+
+    animator.update_parameters(&|x| x.trigger("hit"));
+    assert_eq!(animator.state().key, Animation::Walk);
+    assert_eq!(
+        animator.parameters().get("hit"),
+        Some(ParameterValue::Trigger(false))
+    );
+
+    // the trigger was consumed by the base layer before this layer could use it
+    animator.update(0.5);
+    assert_eq!(animator.layer_state(0).unwrap().key, Animation::Hidden);
+}
+
+#[test]
+fn layers_report_ambiguities() {
+    let mut animator = create_animator();
+    animator
+        .add_layer(
+            StateMachine::new(
+                Animation::Rest,
+                HashMap::from([
+                    (Animation::Rest, State::new(0.5, false)),
+                    (Animation::Attack, State::new(0.5, true)),
+                    (Animation::Flash, State::new(0.5, true)),
+                ]),
+                vec![
+                    Transition::new(
+                        TransitionStartState::Node(Animation::Rest),
+                        TransitionEndState::Node(Animation::Attack),
+                        TransitionTrigger::End,
+                    ),
+                    Transition::new(
+                        TransitionStartState::Node(Animation::Rest),
+                        TransitionEndState::Node(Animation::Flash),
+                        TransitionTrigger::End,
+                    ),
+                ],
+                Params {
+                    speed: 0.0,
+                    attack: false,
+                },
+            )
+            .unwrap(),
+            HashMap::new(),
+            LayerBlending::Additive,
+        )
+        .unwrap();
+    animator.set_report_ambiguities(true);
+
+    assert!(animator.update(0.5).contains(&AnimatorEvent::Layer(
+        2,
+        StateMachineEvent::Ambiguous {
+            transitions: vec![0, 1]
+        }
+    )));
+}
+
+#[test]
+fn layers_update_parameters_events() {
+    let mut animator = create_animator();
+
+    assert_eq!(
+        animator.update_parameters(&|x| {
+            x.speed = 1.0;
+            x.attack = true;
+        }),
+        vec![
+            AnimatorEvent::State(StateMachineEvent::Exited(Animation::Idle)),
+            AnimatorEvent::State(StateMachineEvent::TransitionFired {
+                from: Animation::Idle,
+                to: Animation::Walk,
+                index: 0,
+            }),
+            AnimatorEvent::State(StateMachineEvent::Entered(Animation::Walk)),
+            AnimatorEvent::Layer(0, StateMachineEvent::Exited(Animation::Rest)),
+            AnimatorEvent::Layer(
+                0,
+                StateMachineEvent::TransitionFired {
+                    from: Animation::Rest,
+                    to: Animation::Attack,
+                    index: 0,
+                }
+            ),
+            AnimatorEvent::Layer(0, StateMachineEvent::Entered(Animation::Attack)),
+        ]
+    );
+}
+
+#[test]
+fn layers_fire_events() {
+    let mut animator = create_animator();
+
+    assert_eq!(
+        animator.fire("hit"),
+        vec![
+            AnimatorEvent::Layer(1, StateMachineEvent::Exited(Animation::Hidden)),
+            AnimatorEvent::Layer(
+                1,
+                StateMachineEvent::TransitionFired {
+                    from: Animation::Hidden,
+                    to: Animation::Flash,
+                    index: 0,
+                }
+            ),
+            AnimatorEvent::Layer(1, StateMachineEvent::Entered(Animation::Flash)),
+        ]
+    );
+}
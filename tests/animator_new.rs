@@ -11,13 +11,7 @@ fn animator_new() {
     Animator::new(
         StateMachine::new(
             "idle".to_string(),
-            HashMap::from([(
-                "idle".to_string(),
-                State {
-                    duration: 0.5,
-                    repeat: true,
-                },
-            )]),
+            HashMap::from([("idle".to_string(), State::new(0.5, true))]),
             vec![],
             Params {
                 speed: 1.0,
@@ -51,13 +45,7 @@ fn animator_new_empty_state_frames() {
     let err = Animator::<String, Params, u8>::new(
         StateMachine::new(
             "idle".to_string(),
-            HashMap::from([(
-                "idle".to_string(),
-                State {
-                    duration: 0.5,
-                    repeat: true,
-                },
-            )]),
+            HashMap::from([("idle".to_string(), State::new(0.5, true))]),
             vec![],
             Params {
                 speed: 1.0,
@@ -77,13 +65,7 @@ fn animator_unsorted_state_frames() {
     let err = Animator::<String, Params, u8>::new(
         StateMachine::new(
             "idle".to_string(),
-            HashMap::from([(
-                "idle".to_string(),
-                State {
-                    duration: 0.5,
-                    repeat: true,
-                },
-            )]),
+            HashMap::from([("idle".to_string(), State::new(0.5, true))]),
             vec![],
             Params {
                 speed: 1.0,
@@ -119,13 +101,7 @@ fn animator_invalid_state_frame_progress() {
     let err = Animator::<String, Params, u8>::new(
         StateMachine::new(
             "idle".to_string(),
-            HashMap::from([(
-                "idle".to_string(),
-                State {
-                    duration: 0.5,
-                    repeat: true,
-                },
-            )]),
+            HashMap::from([("idle".to_string(), State::new(0.5, true))]),
             vec![],
             Params {
                 speed: 1.0,
@@ -154,13 +130,7 @@ fn animator_new_missing_state_frames() {
     let err = Animator::<String, Params, u8>::new(
         StateMachine::new(
             "idle".to_string(),
-            HashMap::from([(
-                "idle".to_string(),
-                State {
-                    duration: 0.5,
-                    repeat: true,
-                },
-            )]),
+            HashMap::from([("idle".to_string(), State::new(0.5, true))]),
             vec![],
             Params {
                 speed: 1.0,
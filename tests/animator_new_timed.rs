@@ -0,0 +1,112 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {}
+
+fn create_sm(duration: f32) -> StateMachine<String, Params> {
+    StateMachine::new(
+        "idle".to_string(),
+        HashMap::from([("idle".to_string(), State::new(duration, true))]),
+        vec![],
+        Params {},
+    )
+    .unwrap()
+}
+
+fn create_frames() -> Vec<TimedFrame<u8>> {
+    vec![
+        TimedFrame {
+            duration: 0.1,
+            value: 0,
+        },
+        TimedFrame {
+            duration: 0.3,
+            value: 1,
+        },
+        TimedFrame {
+            duration: 0.1,
+            value: 2,
+        },
+    ]
+}
+
+#[test]
+fn timed_frame_total_duration() {
+    assert_eq!(TimedFrame::total_duration(&create_frames()), 0.5);
+}
+
+#[test]
+fn frame_from_timed() {
+    let frames = Frame::from_timed(create_frames());
+
+    assert_eq!(
+        frames.iter().map(|x| x.progress).collect::<Vec<_>>(),
+        vec![0.0, 0.2, 0.8]
+    );
+    assert_eq!(
+        frames.iter().map(|x| x.value).collect::<Vec<_>>(),
+        vec![0, 1, 2]
+    );
+}
+
+#[test]
+fn animator_new_timed() {
+    let mut animator = Animator::new_timed(
+        create_sm(TimedFrame::total_duration(&create_frames())),
+        HashMap::from([("idle".to_string(), create_frames())]),
+    )
+    .unwrap();
+
+    assert_eq!(animator.frame(), &0);
+
+    animator.update(0.15);
+    assert_eq!(animator.frame(), &1);
+
+    animator.update(0.3);
+    assert_eq!(animator.frame(), &2);
+}
+
+#[test]
+fn animator_new_timed_mismatched_duration() {
+    let error = Animator::new_timed(
+        create_sm(1.0),
+        HashMap::from([("idle".to_string(), create_frames())]),
+    )
+    .expect_err("Expected error");
+
+    assert_eq!(
+        error,
+        AnimatorError::MismatchedStateFrameDuration("idle".to_string(), 0.5)
+    );
+}
+
+#[test]
+fn animator_new_timed_invalid_frame_duration() {
+    let error = Animator::new_timed(
+        create_sm(0.5),
+        HashMap::from([(
+            "idle".to_string(),
+            vec![TimedFrame {
+                duration: -0.5,
+                value: 0,
+            }],
+        )]),
+    )
+    .expect_err("Expected error");
+
+    assert_eq!(
+        error,
+        AnimatorError::InvalidStateFrameDuration("idle".to_string(), -0.5)
+    );
+}
+
+#[test]
+fn animator_new_timed_empty_state_frames() {
+    let error = Animator::new_timed(
+        create_sm(0.0),
+        HashMap::from([("idle".to_string(), Vec::<TimedFrame<u8>>::new())]),
+    )
+    .expect_err("Expected error");
+
+    assert_eq!(error, AnimatorError::EmptyStateFrames("idle".to_string()));
+}
@@ -10,13 +10,7 @@ fn create_animator(starting_state: String, params: Params) -> Animator<String, P
     Animator::<String, Params, u8>::new(
         StateMachine::new(
             starting_state,
-            HashMap::from([(
-                "idle".to_string(),
-                State {
-                    duration: 0.5,
-                    repeat: true,
-                },
-            )]),
+            HashMap::from([("idle".to_string(), State::new(0.5, true))]),
             vec![],
             params,
         )
@@ -0,0 +1,78 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {}
+
+fn create_animator() -> Animator<String, Params, u8> {
+    let mut animator = Animator::new(
+        StateMachine::new(
+            "idle".to_string(),
+            HashMap::from([("idle".to_string(), State::new(1.0, true))]),
+            vec![],
+            Params {},
+        )
+        .unwrap(),
+        HashMap::from([(
+            "idle".to_string(),
+            vec![
+                Frame {
+                    progress: 0.0,
+                    value: 0,
+                },
+                Frame {
+                    progress: 0.5,
+                    value: 1,
+                },
+            ],
+        )]),
+    )
+    .unwrap();
+    animator
+        .add_layer(
+            StateMachine::new(
+                "blink".to_string(),
+                HashMap::from([("blink".to_string(), State::new(1.0, true))]),
+                vec![],
+                Params {},
+            )
+            .unwrap(),
+            HashMap::from([(
+                "blink".to_string(),
+                vec![Frame {
+                    progress: 0.0,
+                    value: 10,
+                }],
+            )]),
+            LayerBlending::Additive,
+        )
+        .unwrap();
+    animator
+}
+
+#[test]
+fn animator_set_speed() {
+    let mut animator = create_animator();
+
+    animator.set_speed(2.0);
+    animator.update(0.25);
+
+    assert_eq!(animator.speed(), 2.0);
+    assert_eq!(animator.state().elapsed, 0.5);
+    assert_eq!(animator.layer_state(0).unwrap().elapsed, 0.5);
+    assert_eq!(animator.frame(), &1);
+}
+
+#[test]
+fn animator_reverse_frame() {
+    let mut animator = create_animator();
+
+    animator.set_speed(-1.0);
+    animator.update(0.25);
+
+    assert_eq!(animator.state().elapsed, 0.75);
+    assert_eq!(animator.frame(), &1);
+
+    animator.update(0.5);
+
+    assert_eq!(animator.frame(), &0);
+}
@@ -0,0 +1,227 @@
+#![cfg(feature = "aseprite")]
+
+use rsanim::prelude::*;
+
+fn sheet_json(frames_hash: bool, tags: &str) -> String {
+    let durations = [100, 100, 200, 100, 50, 50, 50, 50, 100, 100, 100, 100];
+    let frames = durations
+        .iter()
+        .enumerate()
+        .map(|(index, duration)| {
+            let frame = format!(
+                r#"{{ "frame": {{ "x": {}, "y": 0, "w": 16, "h": 16 }}, "duration": {} }}"#,
+                index * 16,
+                duration
+            );
+            if frames_hash {
+                format!(r#""sprite {}.aseprite": {}"#, index, frame)
+            } else {
+                frame
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    let frames = if frames_hash {
+        format!("{{{}}}", frames)
+    } else {
+        format!("[{}]", frames)
+    };
+    format!(
+        r#"{{ "frames": {}, "meta": {{ "app": "https://www.aseprite.org/", "image": "sprite.png", "frameTags": [{}] }} }}"#,
+        frames, tags
+    )
+}
+
+const TAGS: &str = r##"
+    { "name": "idle", "from": 0, "to": 3, "direction": "forward", "color": "#000000ff" },
+    { "name": "run", "from": 4, "to": 7, "direction": "reverse", "color": "#000000ff", "repeat": "2" },
+    { "name": "jump", "from": 8, "to": 11, "direction": "pingpong", "color": "#000000ff" }
+"##;
+
+fn frames(sheet: &AsepriteSheet, tag: &str) -> Vec<(f32, usize)> {
+    sheet.state_frames[tag]
+        .iter()
+        .map(|x| (x.progress, x.value))
+        .collect()
+}
+
+#[test]
+fn aseprite_hash() {
+    let sheet = AsepriteSheet::from_json(&sheet_json(true, TAGS)).unwrap();
+
+    assert_eq!(sheet.states.len(), 3);
+    assert_eq!(sheet.states["idle"], State::new(0.5, true));
+    assert_eq!(
+        frames(&sheet, "idle"),
+        vec![(0.0, 0), (0.2, 1), (0.4, 2), (0.8, 3)]
+    );
+    assert_eq!(
+        sheet.states["run"],
+        State {
+            speed: -1.0,
+            ..State::with_playback(0.2, Playback::LoopCount(2))
+        }
+    );
+    assert_eq!(
+        frames(&sheet, "run"),
+        vec![(0.0, 4), (0.25, 5), (0.5, 6), (0.75, 7)]
+    );
+    assert_eq!(sheet.states["jump"], State::new(0.6, true));
+    assert_eq!(
+        sheet.state_frames["jump"]
+            .iter()
+            .map(|x| x.value)
+            .collect::<Vec<_>>(),
+        vec![8, 9, 10, 11, 10, 9]
+    );
+}
+
+#[test]
+fn aseprite_array() {
+    let sheet = AsepriteSheet::from_json(&sheet_json(false, TAGS)).unwrap();
+
+    assert_eq!(
+        frames(&sheet, "idle"),
+        vec![(0.0, 0), (0.2, 1), (0.4, 2), (0.8, 3)]
+    );
+}
+
+#[test]
+fn aseprite_repeat() {
+    let sheet = AsepriteSheet::from_json(&sheet_json(
+        false,
+        r#"
+            { "name": "once", "from": 0, "to": 1, "direction": "forward", "repeat": "1" },
+            { "name": "bounce", "from": 2, "to": 3, "direction": "pingpong", "repeat": "3" },
+            { "name": "back", "from": 4, "to": 5, "direction": "pingpong_reverse" }
+        "#,
+    ))
+    .unwrap();
+
+    assert_eq!(sheet.states["once"].playback, Playback::Once);
+    assert_eq!(sheet.states["bounce"].playback, Playback::Once);
+    assert_eq!(
+        sheet.state_frames["bounce"]
+            .iter()
+            .map(|x| x.value)
+            .collect::<Vec<_>>(),
+        vec![2, 3, 2, 3, 2, 3, 2]
+    );
+    assert_eq!(sheet.states["back"], State::new(0.1, true));
+    assert_eq!(frames(&sheet, "back"), vec![(0.0, 5), (0.5, 4)]);
+}
+
+#[test]
+fn aseprite_ping_pong_durations() {
+    let sheet = AsepriteSheet::from_json(&sheet_json(true, TAGS)).unwrap();
+
+    let mut animator = Animator::new(
+        StateMachine::new("jump".to_string(), sheet.states, vec![], ()).unwrap(),
+        sheet.state_frames,
+    )
+    .unwrap();
+
+    // sample the middle of every 50ms over a full ping-pong and back to its first frame
+    let mut displayed: Vec<(usize, u32)> = Vec::new();
+    animator.update(0.025);
+    for _ in 0..14 {
+        match displayed.last_mut() {
+            Some((frame, duration)) if frame == animator.frame() => *duration += 50,
+            _ => displayed.push((*animator.frame(), 50)),
+        }
+        animator.update(0.05);
+    }
+
+    assert_eq!(
+        displayed,
+        vec![
+            (8, 100),
+            (9, 100),
+            (10, 100),
+            (11, 100),
+            (10, 100),
+            (9, 100),
+            (8, 100)
+        ]
+    );
+}
+
+#[test]
+fn aseprite_animator() {
+    let sheet = AsepriteSheet::from_json(&sheet_json(true, TAGS)).unwrap();
+
+    let mut animator = Animator::new(
+        StateMachine::new("run".to_string(), sheet.states, vec![], ()).unwrap(),
+        sheet.state_frames,
+    )
+    .unwrap();
+    assert_eq!(*animator.frame(), 7);
+
+    animator.update(0.125);
+    assert_eq!(*animator.frame(), 5);
+}
+
+fn import_err(tags: &str) -> AsepriteError {
+    AsepriteSheet::from_json(&sheet_json(false, tags)).expect_err("Expected error")
+}
+
+#[test]
+fn aseprite_error_json() {
+    let err = AsepriteSheet::from_json("{}").expect_err("Expected error");
+    assert!(matches!(err, AsepriteError::Json(_)));
+}
+
+#[test]
+fn aseprite_error_duplicate_tag() {
+    assert_eq!(
+        import_err(
+            r#"
+                { "name": "idle", "from": 0, "to": 1, "direction": "forward" },
+                { "name": "idle", "from": 2, "to": 3, "direction": "forward" }
+            "#
+        ),
+        AsepriteError::DuplicateTag("idle".to_string())
+    );
+}
+
+#[test]
+fn aseprite_error_tag_range() {
+    assert_eq!(
+        import_err(r#"{ "name": "idle", "from": 3, "to": 2, "direction": "forward" }"#),
+        AsepriteError::InvalidTagRange("idle".to_string())
+    );
+    assert_eq!(
+        import_err(r#"{ "name": "idle", "from": 8, "to": 12, "direction": "forward" }"#),
+        AsepriteError::InvalidTagRange("idle".to_string())
+    );
+}
+
+#[test]
+fn aseprite_error_tag_direction() {
+    assert_eq!(
+        import_err(r#"{ "name": "idle", "from": 0, "to": 3, "direction": "sideways" }"#),
+        AsepriteError::UnknownTagDirection("idle".to_string(), "sideways".to_string())
+    );
+}
+
+#[test]
+fn aseprite_error_tag_repeat() {
+    assert_eq!(
+        import_err(
+            r#"{ "name": "idle", "from": 0, "to": 3, "direction": "forward", "repeat": "0" }"#
+        ),
+        AsepriteError::InvalidTagRepeat("idle".to_string(), "0".to_string())
+    );
+    assert_eq!(
+        import_err(
+            r#"{ "name": "idle", "from": 0, "to": 3, "direction": "forward", "repeat": "many" }"#
+        ),
+        AsepriteError::InvalidTagRepeat("idle".to_string(), "many".to_string())
+    );
+}
+
+#[test]
+fn aseprite_error_tag_malformed() {
+    let err = import_err(r#"{ "name": "idle", "from": -1, "to": 3, "direction": "forward" }"#);
+    assert!(matches!(err, AsepriteError::Json(_)));
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::bool_assert_comparison)]
+
 use rsanim::prelude::*;
 
 #[test]
@@ -6,7 +8,11 @@ fn progress_0() {
         key: "idle".to_string(),
         duration: 0.5,
         elapsed: 0.0,
-        repeat: true,
+        playback: Playback::Loop,
+        speed: 1.0,
+        loops: 0,
+        direction: PlaybackDirection::Forward,
+        parents: vec![],
     };
 
     assert_eq!(current_state.progress(), 0.0);
@@ -18,7 +24,11 @@ fn progress_0_5() {
         key: "idle".to_string(),
         duration: 0.5,
         elapsed: 0.25,
-        repeat: true,
+        playback: Playback::Loop,
+        speed: 1.0,
+        loops: 0,
+        direction: PlaybackDirection::Forward,
+        parents: vec![],
     };
 
     assert_eq!(current_state.progress(), 0.5);
@@ -30,7 +40,11 @@ fn progress_1() {
         key: "idle".to_string(),
         duration: 0.5,
         elapsed: 0.5,
-        repeat: true,
+        playback: Playback::Loop,
+        speed: 1.0,
+        loops: 0,
+        direction: PlaybackDirection::Forward,
+        parents: vec![],
     };
 
     assert_eq!(current_state.progress(), 1.0);
@@ -42,7 +56,11 @@ fn finished_0() {
         key: "idle".to_string(),
         duration: 0.5,
         elapsed: 0.0,
-        repeat: true,
+        playback: Playback::Loop,
+        speed: 1.0,
+        loops: 0,
+        direction: PlaybackDirection::Forward,
+        parents: vec![],
     };
 
     assert_eq!(current_state.finished(), false);
@@ -54,7 +72,11 @@ fn finished_0_5() {
         key: "idle".to_string(),
         duration: 0.5,
         elapsed: 0.25,
-        repeat: true,
+        playback: Playback::Loop,
+        speed: 1.0,
+        loops: 0,
+        direction: PlaybackDirection::Forward,
+        parents: vec![],
     };
 
     assert_eq!(current_state.finished(), false);
@@ -66,7 +88,11 @@ fn finished_1() {
         key: "idle".to_string(),
         duration: 0.5,
         elapsed: 0.5,
-        repeat: true,
+        playback: Playback::Loop,
+        speed: 1.0,
+        loops: 0,
+        direction: PlaybackDirection::Forward,
+        parents: vec![],
     };
 
     assert_eq!(current_state.finished(), true);
@@ -78,11 +104,15 @@ fn debug() {
         key: "idle".to_string(),
         duration: 0.5,
         elapsed: 0.0,
-        repeat: true,
+        playback: Playback::Loop,
+        speed: 1.0,
+        loops: 0,
+        direction: PlaybackDirection::Forward,
+        parents: vec![],
     };
 
     assert_eq!(
         format!("{:?}", current_state),
-        "CurrentState { key: \"idle\", duration: 0.5, elapsed: 0.0, repeat: true }"
+        "CurrentState { key: \"idle\", duration: 0.5, elapsed: 0.0, playback: Loop, speed: 1.0, loops: 0, direction: Forward, parents: [] }"
     );
 }
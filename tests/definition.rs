@@ -0,0 +1,198 @@
+#![cfg(feature = "serde")]
+
+use rsanim::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
+enum Animation {
+    Idle,
+    Run,
+    Jump,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {
+    speed: f32,
+}
+
+const STATE_MACHINE: &str = r#"(
+    starting_state: Idle,
+    states: {
+        Idle: (duration: 0.5, playback: Loop, speed: 1.0, tags: []),
+        Run: (duration: 1.0, playback: Loop, speed: 1.0, tags: ["grounded"]),
+        Jump: (duration: 0.25, playback: Once, speed: 1.0, tags: []),
+    },
+    transitions: [
+        (
+            start_state: Node(Idle),
+            end_state: Node(Run),
+            trigger: Condition("moving"),
+        ),
+        (
+            start_state: Node(Run),
+            end_state: Node(Idle),
+            trigger: Not(Condition("moving")),
+            blend_duration: Some(0.1),
+        ),
+        (
+            start_state: Any,
+            end_state: Node(Jump),
+            trigger: Event("jump"),
+            priority: 1,
+        ),
+        (
+            start_state: Node(Jump),
+            end_state: Previous(resume: false),
+            trigger: End,
+        ),
+    ],
+)"#;
+
+fn registry() -> ConditionRegistry<Animation, Params> {
+    ConditionRegistry::new().with_condition("moving", |x: &Params| x.speed > 0.0)
+}
+
+#[test]
+fn definition_state_machine_ron() {
+    let definition: StateMachineDefinition<Animation> = ron::from_str(STATE_MACHINE).unwrap();
+    assert_eq!(definition.transitions[1].blend_duration, Some(0.1));
+    assert_eq!(definition.transitions[2].priority, 1);
+    assert!(!definition.transitions[0].self_transition);
+
+    let mut sm = definition
+        .build(&registry(), Params { speed: 0.0 })
+        .unwrap();
+    assert_eq!(sm.state().key, Animation::Idle);
+
+    sm.update_parameters(&|x| x.speed = 1.0);
+    assert_eq!(sm.state().key, Animation::Run);
+
+    sm.fire("jump");
+    assert_eq!(sm.state().key, Animation::Jump);
+
+    sm.update(0.3);
+    assert_eq!(sm.state().key, Animation::Run);
+
+    sm.update_parameters(&|x| x.speed = 0.0);
+    assert_eq!(sm.state().key, Animation::Idle);
+}
+
+#[test]
+fn definition_state_defaults() {
+    let state: State = ron::from_str("(duration: 0.5, playback: Loop)").unwrap();
+
+    assert_eq!(state, State::new(0.5, true));
+}
+
+#[test]
+fn definition_unknown_condition() {
+    let definition: StateMachineDefinition<Animation> = ron::from_str(STATE_MACHINE).unwrap();
+
+    let err = definition
+        .build(&ConditionRegistry::new(), Params { speed: 0.0 })
+        .expect_err("Expected error");
+    assert_eq!(err, DefinitionError::UnknownCondition("moving".to_string()));
+}
+
+#[test]
+fn definition_invalid_state_machine() {
+    let mut definition: StateMachineDefinition<Animation> = ron::from_str(STATE_MACHINE).unwrap();
+    definition.states.remove(&Animation::Jump);
+
+    let err = definition
+        .build(&registry(), Params { speed: 0.0 })
+        .expect_err("Expected error");
+    assert_eq!(
+        err,
+        DefinitionError::StateMachine(StateMachineError::InvalidTransitionEndState(
+            Animation::Jump
+        ))
+    );
+}
+
+const ANIMATOR: &str = r#"{
+    "state_machine": {
+        "starting_state": "Idle",
+        "states": {
+            "Idle": { "duration": 0.5, "playback": "Loop", "speed": 1.0, "tags": [] },
+            "Run": { "duration": 1.0, "playback": "Loop", "speed": 1.0, "tags": [] }
+        },
+        "transitions": [
+            {
+                "start_state": { "Node": "Idle" },
+                "end_state": { "Node": "Run" },
+                "trigger": {
+                    "Compare": {
+                        "parameter": "speed",
+                        "operator": "Greater",
+                        "value": { "Float": 0.1 }
+                    }
+                }
+            }
+        ]
+    },
+    "state_frames": {
+        "Idle": [{ "progress": 0.0, "value": 0 }, { "progress": 0.5, "value": 1 }],
+        "Run": [{ "progress": 0.0, "value": 2 }]
+    }
+}"#;
+
+#[test]
+fn definition_animator_json() {
+    let definition: AnimatorDefinition<Animation, u8> = serde_json::from_str(ANIMATOR).unwrap();
+
+    let mut animator = definition
+        .build(&ConditionRegistry::with_parameters(), ParameterMap::new())
+        .unwrap();
+    animator.update(0.3);
+    assert_eq!(*animator.frame(), 1);
+
+    animator.update_parameters(&|x| x.set("speed", 0.5));
+    assert_eq!(animator.state().key, Animation::Run);
+    assert_eq!(*animator.frame(), 2);
+}
+
+#[test]
+fn definition_unsupported_compare() {
+    let definition: AnimatorDefinition<Animation, u8> = serde_json::from_str(ANIMATOR).unwrap();
+
+    let err = definition
+        .build(&ConditionRegistry::new(), ParameterMap::new())
+        .expect_err("Expected error");
+    assert_eq!(
+        err,
+        DefinitionError::UnsupportedCompare("speed".to_string())
+    );
+}
+
+#[test]
+fn definition_round_trip() {
+    let definition: StateMachineDefinition<Animation> = ron::from_str(STATE_MACHINE).unwrap();
+
+    let json = serde_json::to_string(&definition).unwrap();
+    let round_trip: StateMachineDefinition<Animation> = serde_json::from_str(&json).unwrap();
+    assert_eq!(round_trip.starting_state, definition.starting_state);
+    assert_eq!(round_trip.states, definition.states);
+    assert_eq!(round_trip.transitions, definition.transitions);
+}
+
+#[test]
+fn serialize_current_state() {
+    let sm = StateMachine::new(
+        Animation::Run,
+        HashMap::from([(Animation::Run, State::new(1.0, true))]),
+        vec![],
+        Params { speed: 0.0 },
+    )
+    .unwrap();
+
+    let json = serde_json::to_string(sm.state()).unwrap();
+    assert_eq!(
+        json,
+        r#"{"key":"Run","duration":1.0,"elapsed":0.0,"playback":"Loop","speed":1.0,"loops":0,"direction":"Forward","parents":[]}"#
+    );
+    assert_eq!(
+        &serde_json::from_str::<CurrentState<Animation>>(&json).unwrap(),
+        sm.state()
+    );
+}
@@ -10,53 +10,31 @@ fn create_sm(starting_state: String, params: Params) -> StateMachine<String, Par
     StateMachine::new(
         starting_state,
         HashMap::from([
-            (
-                "idle".to_string(),
-                State {
-                    duration: 0.5,
-                    repeat: true,
-                },
+            ("idle".to_string(), State::new(0.5, true)),
+            ("walk".to_string(), State::new(1.0, true)),
+            ("jump".to_string(), State::new(0.25, false)),
+        ]),
+        vec![
+            Transition::new(
+                TransitionStartState::Node("idle".to_string()),
+                TransitionEndState::Node("walk".to_string()),
+                TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed > 0.0 && !x.jump)),
             ),
-            (
-                "walk".to_string(),
-                State {
-                    duration: 1.0,
-                    repeat: true,
-                },
+            Transition::new(
+                TransitionStartState::Node("walk".to_string()),
+                TransitionEndState::Node("idle".to_string()),
+                TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed <= 0.0 && !x.jump)),
             ),
-            (
-                "jump".to_string(),
-                State {
-                    duration: 0.25,
-                    repeat: false,
-                },
+            Transition::new(
+                TransitionStartState::Any,
+                TransitionEndState::Node("jump".to_string()),
+                TransitionTrigger::Condition(Arc::new(|x: &Params| x.jump)),
+            ),
+            Transition::new(
+                TransitionStartState::Node("jump".to_string()),
+                TransitionEndState::Node("walk".to_string()),
+                TransitionTrigger::End,
             ),
-        ]),
-        vec![
-            Transition {
-                start_state: TransitionStartState::Node("idle".to_string()),
-                end_state: TransitionEndState::Node("walk".to_string()),
-                trigger: TransitionTrigger::Condition(Box::new(|x: &Params| {
-                    x.speed > 0.0 && !x.jump
-                })),
-            },
-            Transition {
-                start_state: TransitionStartState::Node("walk".to_string()),
-                end_state: TransitionEndState::Node("idle".to_string()),
-                trigger: TransitionTrigger::Condition(Box::new(|x: &Params| {
-                    x.speed <= 0.0 && !x.jump
-                })),
-            },
-            Transition {
-                start_state: TransitionStartState::Any,
-                end_state: TransitionEndState::Node("jump".to_string()),
-                trigger: TransitionTrigger::Condition(Box::new(|x: &Params| x.jump)),
-            },
-            Transition {
-                start_state: TransitionStartState::Node("jump".to_string()),
-                end_state: TransitionEndState::Node("walk".to_string()),
-                trigger: TransitionTrigger::End,
-            },
         ],
         params,
     )
@@ -79,7 +57,11 @@ fn starts_in_starting_state() {
             key: "idle".to_string(),
             duration: 0.5,
             elapsed: 0.0,
-            repeat: true,
+            playback: Playback::Loop,
+            speed: 1.0,
+            loops: 0,
+            direction: PlaybackDirection::Forward,
+            parents: vec![],
         }
     );
 }
@@ -111,7 +93,7 @@ fn walk_repeats() {
     let mut sm = create_sm(
         "walk".to_string(),
         Params {
-            speed: 0.0,
+            speed: 1.0,
             jump: false,
         },
     );
@@ -148,7 +130,11 @@ fn transition_idle_to_walk() {
             key: "walk".to_string(),
             duration: 1.0,
             elapsed: 0.0,
-            repeat: true,
+            playback: Playback::Loop,
+            speed: 1.0,
+            loops: 0,
+            direction: PlaybackDirection::Forward,
+            parents: vec![],
         }
     );
 }
@@ -173,7 +159,11 @@ fn transition_idle_to_jump() {
             key: "jump".to_string(),
             duration: 0.25,
             elapsed: 0.0,
-            repeat: false,
+            playback: Playback::Once,
+            speed: 1.0,
+            loops: 0,
+            direction: PlaybackDirection::Forward,
+            parents: vec![],
         }
     );
 }
@@ -198,7 +188,11 @@ fn transition_walk_to_idle() {
             key: "idle".to_string(),
             duration: 0.5,
             elapsed: 0.0,
-            repeat: true,
+            playback: Playback::Loop,
+            speed: 1.0,
+            loops: 0,
+            direction: PlaybackDirection::Forward,
+            parents: vec![],
         }
     );
 }
@@ -223,7 +217,11 @@ fn transition_walk_to_jump() {
             key: "jump".to_string(),
             duration: 0.25,
             elapsed: 0.0,
-            repeat: false,
+            playback: Playback::Once,
+            speed: 1.0,
+            loops: 0,
+            direction: PlaybackDirection::Forward,
+            parents: vec![],
         }
     );
 }
@@ -248,7 +246,11 @@ fn transition_end_jump_to_idle() {
             key: "jump".to_string(),
             duration: 0.25,
             elapsed: 0.0,
-            repeat: false,
+            playback: Playback::Once,
+            speed: 1.0,
+            loops: 0,
+            direction: PlaybackDirection::Forward,
+            parents: vec![],
         }
     );
 
@@ -260,7 +262,11 @@ fn transition_end_jump_to_idle() {
             key: "walk".to_string(),
             duration: 1.0,
             elapsed: 0.0,
-            repeat: true,
+            playback: Playback::Loop,
+            speed: 1.0,
+            loops: 0,
+            direction: PlaybackDirection::Forward,
+            parents: vec![],
         }
     );
 }
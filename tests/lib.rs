@@ -1,15 +1,43 @@
 mod animator;
+mod animator_blend_space;
+mod animator_blended_frame;
 mod animator_error;
+mod animator_events;
+mod animator_layers;
 mod animator_new;
+mod animator_new_timed;
+mod animator_speed;
+#[cfg(feature = "aseprite")]
+mod aseprite;
 mod current_state;
+#[cfg(feature = "serde")]
+mod definition;
 mod example;
 mod example_sm;
 mod state_machine;
+mod state_machine_blend;
+mod state_machine_compare;
+mod state_machine_context;
 mod state_machine_error;
+mod state_machine_evaluation_policy;
+mod state_machine_events;
+mod state_machine_exit_time;
+mod state_machine_fire;
 mod state_machine_new;
 mod state_machine_parameters;
+mod state_machine_play;
+mod state_machine_playback;
+mod state_machine_previous;
+mod state_machine_priority;
+mod state_machine_random;
+mod state_machine_self_transition;
+mod state_machine_speed;
+mod state_machine_start_state;
 mod state_machine_state;
+mod state_machine_sub_machine;
+mod state_machine_update;
 mod transition;
 mod transition_end_state;
 mod transition_start_state;
 mod transition_trigger;
+mod transition_trigger_combinators;
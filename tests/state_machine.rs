@@ -15,13 +15,7 @@ struct Params {
 fn clone() {
     let sm = StateMachine::new(
         Animation::Idle,
-        HashMap::from([(
-            Animation::Idle,
-            State {
-                duration: 0.5,
-                repeat: true,
-            },
-        )]),
+        HashMap::from([(Animation::Idle, State::new(0.5, true))]),
         vec![],
         Params {
             speed: 0.0,
@@ -37,13 +31,7 @@ fn clone() {
 fn debug() {
     let sm = StateMachine::new(
         Animation::Idle,
-        HashMap::from([(
-            Animation::Idle,
-            State {
-                duration: 0.5,
-                repeat: true,
-            },
-        )]),
+        HashMap::from([(Animation::Idle, State::new(0.5, true))]),
         vec![],
         Params {
             speed: 0.0,
@@ -54,6 +42,13 @@ fn debug() {
 
     assert_eq!(
         format!("{:?}", sm),
-        "StateMachine { current_state: CurrentState { key: Idle, duration: 0.5, elapsed: 0.0, repeat: true }, states: {Idle: State { duration: 0.5, repeat: true }}, transitions: [], parameters: Params { speed: 0.0, jump: false } }"
+        "StateMachine { current_state: CurrentState { key: Idle, duration: 0.5, elapsed: 0.0, playback: Loop, speed: 1.0, loops: 0, direction: Forward, parents: [] }, states: {Idle: State { duration: 0.5, playback: Loop, speed: 1.0, tags: [] }}, transitions: [], parameters: Params { speed: 0.0, jump: false }, events: [], event_lifetime: 1, speed: 1.0, blend: None, scopes: [], parents: {}, entry_states: {}, latched: {}, history: [], history_depth: 1, rng: 2685821657736338717, report_ambiguities: false, previous_state: None, time_in_state: 0.0, evaluation_policy: EveryUpdate, frame_progress: {} }"
     );
 }
+
+#[test]
+fn send_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<StateMachine<Animation, Params>>();
+}
@@ -0,0 +1,152 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Animation {
+    Idle,
+    Walk,
+    Jump,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {
+    pub speed: f32,
+    pub jump: bool,
+}
+
+fn create_sm(starting_state: Animation, params: Params) -> StateMachine<Animation, Params> {
+    StateMachine::new(
+        starting_state,
+        HashMap::from([
+            (Animation::Idle, State::new(0.5, true)),
+            (Animation::Walk, State::new(1.0, true)),
+            (Animation::Jump, State::new(0.25, false)),
+        ]),
+        vec![
+            Transition {
+                blend_duration: Some(0.2),
+                ..Transition::new(
+                    TransitionStartState::Node(Animation::Idle),
+                    TransitionEndState::Node(Animation::Walk),
+                    TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed > 0.0)),
+                )
+            },
+            Transition::new(
+                TransitionStartState::Any,
+                TransitionEndState::Node(Animation::Jump),
+                TransitionTrigger::Condition(Arc::new(|x: &Params| x.jump)),
+            ),
+        ],
+        params,
+    )
+    .unwrap()
+}
+
+#[test]
+fn blend_none() {
+    let sm = create_sm(
+        Animation::Idle,
+        Params {
+            speed: 0.0,
+            jump: false,
+        },
+    );
+
+    assert_eq!(sm.blend(), None);
+}
+
+#[test]
+fn blend_started() {
+    let mut sm = create_sm(
+        Animation::Idle,
+        Params {
+            speed: 0.0,
+            jump: false,
+        },
+    );
+
+    sm.update(0.25);
+    sm.update_parameters(&|x| {
+        x.speed = 1.0;
+    });
+
+    assert_eq!(sm.state().key, Animation::Walk);
+    assert_eq!(
+        sm.blend(),
+        Some(&Blend {
+            from: CurrentState {
+                key: Animation::Idle,
+                duration: 0.5,
+                elapsed: 0.25,
+                playback: Playback::Loop,
+                speed: 1.0,
+                loops: 0,
+                direction: PlaybackDirection::Forward,
+                parents: vec![],
+            },
+            duration: 0.2,
+            elapsed: 0.0,
+        })
+    );
+    assert_eq!(sm.blend().unwrap().weight(), 1.0);
+}
+
+#[test]
+fn blend_outgoing_state_keeps_playing() {
+    let mut sm = create_sm(
+        Animation::Idle,
+        Params {
+            speed: 0.0,
+            jump: false,
+        },
+    );
+
+    sm.update(0.25);
+    sm.update_parameters(&|x| {
+        x.speed = 1.0;
+    });
+    sm.update(0.1);
+
+    let blend = sm.blend().unwrap();
+    assert_eq!(blend.from.elapsed, 0.35);
+    assert_eq!(blend.weight(), 0.5);
+    assert_eq!(sm.state().elapsed, 0.1);
+}
+
+#[test]
+fn blend_completed() {
+    let mut sm = create_sm(
+        Animation::Idle,
+        Params {
+            speed: 0.0,
+            jump: false,
+        },
+    );
+
+    sm.update_parameters(&|x| {
+        x.speed = 1.0;
+    });
+    sm.update(0.2);
+
+    assert_eq!(sm.blend(), None);
+}
+
+#[test]
+fn blend_cancelled_by_cut() {
+    let mut sm = create_sm(
+        Animation::Idle,
+        Params {
+            speed: 0.0,
+            jump: false,
+        },
+    );
+
+    sm.update_parameters(&|x| {
+        x.speed = 1.0;
+    });
+    sm.update_parameters(&|x| {
+        x.jump = true;
+    });
+
+    assert_eq!(sm.state().key, Animation::Jump);
+    assert_eq!(sm.blend(), None);
+}
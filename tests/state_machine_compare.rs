@@ -0,0 +1,180 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Animation {
+    Idle,
+    Walk,
+    Attack,
+}
+
+fn create_sm(
+    trigger: TransitionTrigger<Animation, ParameterMap>,
+) -> StateMachine<Animation, ParameterMap> {
+    StateMachine::new(
+        Animation::Idle,
+        HashMap::from([
+            (Animation::Idle, State::new(1.0, true)),
+            (Animation::Walk, State::new(1.0, true)),
+            (Animation::Attack, State::new(1.0, false)),
+        ]),
+        vec![Transition::new(
+            TransitionStartState::Node(Animation::Idle),
+            TransitionEndState::Node(Animation::Walk),
+            trigger,
+        )],
+        ParameterMap::new(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn parameter_map() {
+    let mut parameters = ParameterMap::new();
+    assert_eq!(parameters.get("speed"), None);
+
+    parameters.set("speed", 0.5);
+    parameters.set("lives", 3);
+    parameters.set("grounded", true);
+    parameters.trigger("attack");
+
+    assert_eq!(parameters.get("speed"), Some(ParameterValue::Float(0.5)));
+    assert_eq!(parameters.get("lives"), Some(ParameterValue::Int(3)));
+    assert_eq!(parameters.get("grounded"), Some(ParameterValue::Bool(true)));
+    assert_eq!(
+        parameters.get("attack"),
+        Some(ParameterValue::Trigger(true))
+    );
+
+    parameters.reset_trigger("attack");
+    parameters.reset_trigger("grounded");
+    assert_eq!(
+        parameters.get("attack"),
+        Some(ParameterValue::Trigger(false))
+    );
+    assert_eq!(parameters.get("grounded"), Some(ParameterValue::Bool(true)));
+}
+
+#[test]
+fn sm_compare_float() {
+    let mut sm = create_sm(TransitionTrigger::Compare(Comparison::new(
+        "speed",
+        ComparisonOperator::Greater,
+        0.1,
+    )));
+
+    sm.update_parameters(&|x| x.set("speed", 0.1));
+    assert_eq!(sm.state().key, Animation::Idle);
+
+    sm.update_parameters(&|x| x.set("speed", 0.5));
+    assert_eq!(sm.state().key, Animation::Walk);
+}
+
+#[test]
+fn sm_compare_int_with_float() {
+    let mut sm = create_sm(TransitionTrigger::Compare(Comparison::new(
+        "lives",
+        ComparisonOperator::LessOrEqual,
+        1.5,
+    )));
+
+    sm.update_parameters(&|x| x.set("lives", 2));
+    assert_eq!(sm.state().key, Animation::Idle);
+
+    sm.update_parameters(&|x| x.set("lives", 1));
+    assert_eq!(sm.state().key, Animation::Walk);
+}
+
+#[test]
+fn sm_compare_missing_or_mismatched() {
+    let mut sm = create_sm(TransitionTrigger::Compare(Comparison::new(
+        "speed",
+        ComparisonOperator::NotEqual,
+        0.0,
+    )));
+
+    sm.update(0.1);
+    assert_eq!(sm.state().key, Animation::Idle);
+
+    sm.update_parameters(&|x| x.set("speed", true));
+    assert_eq!(sm.state().key, Animation::Idle);
+}
+
+#[test]
+fn sm_compare_triggered() {
+    let mut sm = create_sm(TransitionTrigger::Compare(Comparison::triggered("attack")));
+
+    sm.update_parameters(&|x| x.trigger("attack"));
+    assert_eq!(sm.state().key, Animation::Walk);
+    assert_eq!(
+        sm.parameters().get("attack"),
+        Some(ParameterValue::Trigger(false))
+    );
+}
+
+#[test]
+fn sm_compare_negated_not_reset() {
+    let mut sm = create_sm(TransitionTrigger::Not(Box::new(
+        TransitionTrigger::Compare(Comparison::new("attack", ComparisonOperator::Equal, false)),
+    )));
+
+    sm.update_parameters(&|x| x.trigger("attack"));
+    assert_eq!(sm.state().key, Animation::Walk);
+    assert_eq!(
+        sm.parameters().get("attack"),
+        Some(ParameterValue::Trigger(true))
+    );
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {
+    speed: f32,
+}
+
+impl Parameters for Params {
+    fn parameter(&self, name: &str) -> Option<ParameterValue> {
+        match name {
+            "speed" => Some(self.speed.into()),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn sm_compare_parameters() {
+    let mut sm = StateMachine::new(
+        Animation::Idle,
+        HashMap::from([
+            (Animation::Idle, State::new(1.0, true)),
+            (Animation::Walk, State::new(1.0, true)),
+        ]),
+        vec![Transition::new(
+            TransitionStartState::Node(Animation::Idle),
+            TransitionEndState::Node(Animation::Walk),
+            TransitionTrigger::Compare(Comparison::new(
+                "speed",
+                ComparisonOperator::GreaterOrEqual,
+                1,
+            )),
+        )],
+        Params { speed: 0.0 },
+    )
+    .unwrap();
+
+    sm.update_parameters(&|x| x.speed = 1.0);
+    assert_eq!(sm.state().key, Animation::Walk);
+}
+
+#[test]
+fn debug_compare() {
+    assert_eq!(
+        format!(
+            "{:?}",
+            TransitionTrigger::<(), ParameterMap>::Compare(Comparison::new(
+                "speed",
+                ComparisonOperator::Greater,
+                0.1
+            ))
+        ),
+        "Compare(Comparison { parameter: \"speed\", operator: Greater, value: Float(0.1) })"
+    );
+}
@@ -0,0 +1,126 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Animation {
+    Idle,
+    Crouch,
+    Jump,
+    Fall,
+    Land,
+    Roll,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {
+    crouch: bool,
+}
+
+fn create_sm(
+    starting_state: Animation,
+    transitions: Vec<Transition<Animation, Params>>,
+) -> StateMachine<Animation, Params> {
+    StateMachine::new(
+        starting_state,
+        HashMap::from([
+            (Animation::Idle, State::new(1.0, true)),
+            (Animation::Crouch, State::new(1.0, true)),
+            (Animation::Jump, State::new(1.0, false)),
+            (Animation::Fall, State::new(1.0, true)),
+            (Animation::Land, State::new(0.5, false)),
+            (Animation::Roll, State::new(0.5, false)),
+        ]),
+        transitions,
+        Params { crouch: true },
+    )
+    .unwrap()
+}
+
+#[test]
+fn sm_context_time_in_state() {
+    let mut sm = create_sm(
+        Animation::Crouch,
+        vec![Transition::new(
+            TransitionStartState::Node(Animation::Crouch),
+            TransitionEndState::Node(Animation::Idle),
+            TransitionTrigger::Context(Arc::new(|x| {
+                !x.parameters.crouch && x.time_in_state >= 0.2
+            })),
+        )],
+    );
+
+    sm.update_parameters(&|x| x.crouch = false);
+    assert_eq!(sm.state().key, Animation::Crouch);
+
+    sm.update(0.15);
+    assert_eq!(sm.state().key, Animation::Crouch);
+
+    sm.update(0.15);
+    assert_eq!(sm.state().key, Animation::Idle);
+}
+
+#[test]
+fn sm_context_time_in_state_carried() {
+    let mut sm = create_sm(
+        Animation::Land,
+        vec![
+            Transition::new(
+                TransitionStartState::Node(Animation::Land),
+                TransitionEndState::Node(Animation::Idle),
+                TransitionTrigger::End,
+            ),
+            Transition::new(
+                TransitionStartState::Node(Animation::Idle),
+                TransitionEndState::Node(Animation::Crouch),
+                TransitionTrigger::Context(Arc::new(|x| x.time_in_state >= 0.2)),
+            ),
+        ],
+    );
+
+    sm.update(0.6);
+    assert_eq!(sm.state().key, Animation::Idle);
+
+    sm.update(0.15);
+    assert_eq!(sm.state().key, Animation::Crouch);
+}
+
+#[test]
+fn sm_context_progress() {
+    let mut sm = create_sm(
+        Animation::Jump,
+        vec![Transition::new(
+            TransitionStartState::Node(Animation::Jump),
+            TransitionEndState::Node(Animation::Fall),
+            TransitionTrigger::Context(Arc::new(|x| x.state.progress() > 0.5)),
+        )],
+    );
+
+    sm.update(0.4);
+    assert_eq!(sm.state().key, Animation::Jump);
+
+    sm.update(0.2);
+    assert_eq!(sm.state().key, Animation::Fall);
+}
+
+#[test]
+fn sm_context_previous_state() {
+    let transitions = vec![
+        Transition::new(
+            TransitionStartState::Any,
+            TransitionEndState::Node(Animation::Land),
+            TransitionTrigger::Event("land".to_string()),
+        ),
+        Transition::new(
+            TransitionStartState::Node(Animation::Land),
+            TransitionEndState::Node(Animation::Roll),
+            TransitionTrigger::Context(Arc::new(|x| x.previous_state == Some(&Animation::Fall))),
+        ),
+    ];
+
+    let mut sm = create_sm(Animation::Idle, transitions.clone());
+    sm.fire("land");
+    assert_eq!(sm.state().key, Animation::Land);
+
+    let mut sm = create_sm(Animation::Fall, transitions);
+    sm.fire("land");
+    assert_eq!(sm.state().key, Animation::Roll);
+}
@@ -0,0 +1,86 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Animation {
+    Idle,
+    Walk,
+    Crouch,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {
+    speed: f32,
+}
+
+fn create_sm(evaluation_policy: EvaluationPolicy) -> StateMachine<Animation, Params> {
+    let mut sm = StateMachine::new(
+        Animation::Walk,
+        HashMap::from([
+            (Animation::Idle, State::new(1.0, true)),
+            (Animation::Walk, State::new(1.0, true)),
+            (Animation::Crouch, State::new(1.0, true)),
+        ]),
+        vec![
+            Transition::new(
+                TransitionStartState::Node(Animation::Walk),
+                TransitionEndState::Node(Animation::Idle),
+                TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed <= 0.0)),
+            ),
+            Transition::new(
+                TransitionStartState::Node(Animation::Idle),
+                TransitionEndState::Node(Animation::Crouch),
+                TransitionTrigger::Context(Arc::new(|x| x.time_in_state >= 0.5)),
+            ),
+        ],
+        Params { speed: 0.0 },
+    )
+    .unwrap();
+    sm.set_evaluation_policy(evaluation_policy);
+    sm
+}
+
+#[test]
+fn sm_evaluation_policy_default() {
+    let sm = StateMachine::new(
+        Animation::Idle,
+        HashMap::from([(Animation::Idle, State::new(1.0, true))]),
+        vec![],
+        Params { speed: 0.0 },
+    )
+    .unwrap();
+
+    assert_eq!(sm.evaluation_policy(), EvaluationPolicy::EveryUpdate);
+}
+
+#[test]
+fn sm_evaluation_policy_every_update() {
+    let mut sm = create_sm(EvaluationPolicy::EveryUpdate);
+
+    assert_eq!(
+        sm.update(0.1),
+        vec![
+            StateMachineEvent::Exited(Animation::Walk),
+            StateMachineEvent::TransitionFired {
+                from: Animation::Walk,
+                to: Animation::Idle,
+                index: 0,
+            },
+            StateMachineEvent::Entered(Animation::Idle),
+        ]
+    );
+}
+
+#[test]
+fn sm_evaluation_policy_parameter_changes() {
+    let mut sm = create_sm(EvaluationPolicy::ParameterChanges);
+
+    assert_eq!(sm.update(0.1), vec![]);
+    assert_eq!(sm.state().key, Animation::Walk);
+
+    sm.update_parameters(&|_| {});
+    assert_eq!(sm.state().key, Animation::Idle);
+
+    // Context conditions are still checked on time updates
+    sm.update(0.5);
+    assert_eq!(sm.state().key, Animation::Crouch);
+}
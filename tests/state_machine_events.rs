@@ -0,0 +1,204 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Animation {
+    Ground,
+    Idle,
+    Walk,
+    Jump,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {
+    pub speed: f32,
+}
+
+fn create_sm() -> StateMachine<Animation, Params> {
+    StateMachine::new_nested(
+        Animation::Ground,
+        HashMap::from([(Animation::Jump, State::new(0.5, false))]),
+        HashMap::from([(
+            Animation::Ground,
+            SubStateMachine {
+                entry_state: Animation::Idle,
+                states: HashMap::from([
+                    (
+                        Animation::Idle,
+                        State::with_playback(0.5, Playback::LoopCount(2)),
+                    ),
+                    (Animation::Walk, State::new(1.0, true)),
+                ]),
+                sub_machines: HashMap::new(),
+                transitions: vec![Transition::new(
+                    TransitionStartState::Node(Animation::Idle),
+                    TransitionEndState::Node(Animation::Walk),
+                    TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed > 0.0)),
+                )],
+            },
+        )]),
+        vec![
+            Transition::new(
+                TransitionStartState::Node(Animation::Ground),
+                TransitionEndState::Node(Animation::Jump),
+                TransitionTrigger::Event("jump".to_string()),
+            ),
+            Transition::new(
+                TransitionStartState::Node(Animation::Jump),
+                TransitionEndState::Node(Animation::Ground),
+                TransitionTrigger::End,
+            ),
+        ],
+        Params { speed: 0.0 },
+    )
+    .unwrap()
+}
+
+#[test]
+fn sm_events_none() {
+    let mut sm = create_sm();
+
+    assert_eq!(sm.update(0.25), vec![]);
+}
+
+#[test]
+fn sm_events_looped_finished() {
+    let mut sm = create_sm();
+
+    assert_eq!(
+        sm.update(1.0),
+        vec![
+            StateMachineEvent::Looped(Animation::Idle),
+            StateMachineEvent::Finished(Animation::Idle),
+        ]
+    );
+}
+
+#[test]
+fn sm_events_update_parameters() {
+    let mut sm = create_sm();
+
+    assert_eq!(
+        sm.update_parameters(&|x| x.speed = 1.0),
+        vec![
+            StateMachineEvent::Exited(Animation::Idle),
+            StateMachineEvent::TransitionFired {
+                from: Animation::Idle,
+                to: Animation::Walk,
+                index: 2,
+            },
+            StateMachineEvent::Entered(Animation::Walk),
+        ]
+    );
+}
+
+#[test]
+fn sm_events_fire_exits_parent() {
+    let mut sm = create_sm();
+
+    assert_eq!(
+        sm.fire("jump"),
+        vec![
+            StateMachineEvent::Exited(Animation::Idle),
+            StateMachineEvent::Exited(Animation::Ground),
+            StateMachineEvent::TransitionFired {
+                from: Animation::Idle,
+                to: Animation::Jump,
+                index: 0,
+            },
+            StateMachineEvent::Entered(Animation::Jump),
+        ]
+    );
+}
+
+#[test]
+fn sm_events_chained() {
+    let mut sm = create_sm();
+    sm.fire("jump");
+    sm.update_parameters(&|x| x.speed = 1.0);
+
+    // the jump ends into the ground, whose idle immediately transitions to the walk
+    assert_eq!(
+        sm.update(0.5),
+        vec![
+            StateMachineEvent::Finished(Animation::Jump),
+            StateMachineEvent::Exited(Animation::Jump),
+            StateMachineEvent::TransitionFired {
+                from: Animation::Jump,
+                to: Animation::Idle,
+                index: 1,
+            },
+            StateMachineEvent::Entered(Animation::Ground),
+            StateMachineEvent::Entered(Animation::Idle),
+            StateMachineEvent::Exited(Animation::Idle),
+            StateMachineEvent::TransitionFired {
+                from: Animation::Idle,
+                to: Animation::Walk,
+                index: 2,
+            },
+            StateMachineEvent::Entered(Animation::Walk),
+        ]
+    );
+}
+
+#[test]
+fn sm_events_loop_end_transition() {
+    let mut sm = StateMachine::new(
+        Animation::Walk,
+        HashMap::from([
+            (Animation::Walk, State::new(1.0, true)),
+            (Animation::Idle, State::new(1.0, true)),
+        ]),
+        vec![Transition::new(
+            TransitionStartState::Node(Animation::Walk),
+            TransitionEndState::Node(Animation::Idle),
+            TransitionTrigger::End,
+        )],
+        Params { speed: 0.0 },
+    )
+    .unwrap();
+
+    assert_eq!(
+        sm.update(2.5),
+        vec![
+            StateMachineEvent::Looped(Animation::Walk),
+            StateMachineEvent::Exited(Animation::Walk),
+            StateMachineEvent::TransitionFired {
+                from: Animation::Walk,
+                to: Animation::Idle,
+                index: 0,
+            },
+            StateMachineEvent::Entered(Animation::Idle),
+            StateMachineEvent::Looped(Animation::Idle),
+        ]
+    );
+}
+
+#[test]
+fn sm_events_skip_loops() {
+    for playback in [Playback::Loop, Playback::PingPong] {
+        let mut sm = StateMachine::new(
+            Animation::Walk,
+            HashMap::from([(Animation::Walk, State::with_playback(0.01, playback))]),
+            vec![],
+            Params { speed: 0.0 },
+        )
+        .unwrap();
+
+        // the whole loops past the first are skipped at once
+        assert_eq!(
+            sm.update(100.0),
+            vec![
+                StateMachineEvent::Looped(Animation::Walk),
+                StateMachineEvent::Looped(Animation::Walk),
+            ]
+        );
+        assert_eq!(
+            sm.update(1.0e6),
+            vec![
+                StateMachineEvent::Looped(Animation::Walk),
+                StateMachineEvent::Looped(Animation::Walk),
+            ]
+        );
+        assert!(sm.state().loops > 1000);
+    }
+}
@@ -0,0 +1,143 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Animation {
+    Idle,
+    Walk,
+    Attack,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {
+    pub speed: f32,
+    pub attack: bool,
+}
+
+fn create_sm(starting_state: Animation, params: Params) -> StateMachine<Animation, Params> {
+    StateMachine::new(
+        starting_state,
+        HashMap::from([
+            (Animation::Idle, State::new(0.5, true)),
+            (Animation::Walk, State::new(1.0, true)),
+            (Animation::Attack, State::new(1.0, false)),
+        ]),
+        vec![
+            Transition::new(
+                TransitionStartState::Node(Animation::Idle),
+                TransitionEndState::Node(Animation::Attack),
+                TransitionTrigger::Condition(Arc::new(|x: &Params| x.attack)),
+            ),
+            Transition {
+                exit_time: Some(0.5),
+                ..Transition::new(
+                    TransitionStartState::Node(Animation::Attack),
+                    TransitionEndState::Node(Animation::Idle),
+                    TransitionTrigger::Condition(Arc::new(|x: &Params| !x.attack)),
+                )
+            },
+            Transition {
+                exit_time: Some(0.9),
+                ..Transition::new(
+                    TransitionStartState::Node(Animation::Walk),
+                    TransitionEndState::Node(Animation::Idle),
+                    TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed <= 0.0)),
+                )
+            },
+        ],
+        params,
+    )
+    .unwrap()
+}
+
+#[test]
+fn exit_time_blocks_condition() {
+    let mut sm = create_sm(
+        Animation::Attack,
+        Params {
+            speed: 0.0,
+            attack: true,
+        },
+    );
+
+    sm.update_parameters(&|x| {
+        x.attack = false;
+    });
+    assert_eq!(sm.state().key, Animation::Attack);
+
+    sm.update(0.25);
+    assert_eq!(sm.state().key, Animation::Attack);
+}
+
+#[test]
+fn exit_time_reached() {
+    let mut sm = create_sm(
+        Animation::Attack,
+        Params {
+            speed: 0.0,
+            attack: true,
+        },
+    );
+
+    sm.update_parameters(&|x| {
+        x.attack = false;
+    });
+    sm.update(0.25);
+    sm.update(0.25);
+
+    assert_eq!(sm.state().key, Animation::Idle);
+}
+
+#[test]
+fn exit_time_already_reached() {
+    let mut sm = create_sm(
+        Animation::Attack,
+        Params {
+            speed: 0.0,
+            attack: true,
+        },
+    );
+
+    sm.update(0.75);
+    assert_eq!(sm.state().key, Animation::Attack);
+
+    sm.update_parameters(&|x| {
+        x.attack = false;
+    });
+    assert_eq!(sm.state().key, Animation::Idle);
+}
+
+#[test]
+fn exit_time_end_of_loop() {
+    let mut sm = create_sm(
+        Animation::Walk,
+        Params {
+            speed: 1.0,
+            attack: false,
+        },
+    );
+
+    sm.update(0.5);
+    sm.update_parameters(&|x| {
+        x.speed = 0.0;
+    });
+    assert_eq!(sm.state().key, Animation::Walk);
+
+    // wraps past the end of the loop without landing on the exit time
+    sm.update(0.55);
+    assert_eq!(sm.state().key, Animation::Idle);
+}
+
+#[test]
+fn exit_time_condition_not_met() {
+    let mut sm = create_sm(
+        Animation::Attack,
+        Params {
+            speed: 0.0,
+            attack: true,
+        },
+    );
+
+    sm.update(0.75);
+
+    assert_eq!(sm.state().key, Animation::Attack);
+}
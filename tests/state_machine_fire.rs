@@ -0,0 +1,106 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Animation {
+    Land,
+    Idle,
+    Attack,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {}
+
+fn create_sm(starting_state: Animation) -> StateMachine<Animation, Params> {
+    StateMachine::new(
+        starting_state,
+        HashMap::from([
+            (Animation::Land, State::new(0.5, false)),
+            (Animation::Idle, State::new(0.5, true)),
+            (Animation::Attack, State::new(0.25, false)),
+        ]),
+        vec![
+            Transition::new(
+                TransitionStartState::Node(Animation::Land),
+                TransitionEndState::Node(Animation::Idle),
+                TransitionTrigger::End,
+            ),
+            Transition::new(
+                TransitionStartState::Node(Animation::Idle),
+                TransitionEndState::Node(Animation::Attack),
+                TransitionTrigger::Event("attack".to_string()),
+            ),
+            Transition::new(
+                TransitionStartState::Node(Animation::Attack),
+                TransitionEndState::Node(Animation::Idle),
+                TransitionTrigger::End,
+            ),
+        ],
+        Params {},
+    )
+    .unwrap()
+}
+
+#[test]
+fn sm_fire() {
+    let mut sm = create_sm(Animation::Idle);
+
+    sm.fire("attack");
+
+    assert_eq!(sm.state().key, Animation::Attack);
+}
+
+#[test]
+fn sm_fire_unknown_event() {
+    let mut sm = create_sm(Animation::Idle);
+
+    sm.fire("jump");
+
+    assert_eq!(sm.state().key, Animation::Idle);
+}
+
+#[test]
+fn sm_fire_consumed() {
+    let mut sm = create_sm(Animation::Idle);
+
+    sm.fire("attack");
+    sm.update(0.25);
+
+    // the event was consumed by the first attack, so it does not trigger another
+    assert_eq!(sm.state().key, Animation::Idle);
+}
+
+#[test]
+fn sm_fire_pending() {
+    let mut sm = create_sm(Animation::Land);
+
+    sm.fire("attack");
+    assert_eq!(sm.state().key, Animation::Land);
+
+    sm.update(0.5);
+    assert_eq!(sm.state().key, Animation::Attack);
+}
+
+#[test]
+fn sm_fire_dropped() {
+    let mut sm = create_sm(Animation::Land);
+
+    sm.fire("attack");
+    sm.update(0.25);
+    sm.update(0.25);
+
+    assert_eq!(sm.state().key, Animation::Idle);
+}
+
+#[test]
+fn sm_fire_event_lifetime() {
+    let mut sm = create_sm(Animation::Land);
+    sm.set_event_lifetime(2);
+
+    assert_eq!(sm.event_lifetime(), 2);
+
+    sm.fire("attack");
+    sm.update(0.25);
+    sm.update(0.25);
+
+    assert_eq!(sm.state().key, Animation::Attack);
+}
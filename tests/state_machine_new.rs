@@ -16,13 +16,7 @@ struct Params {
 fn sm_new() {
     StateMachine::new(
         Animation::Idle,
-        HashMap::from([(
-            Animation::Idle,
-            State {
-                duration: 0.5,
-                repeat: true,
-            },
-        )]),
+        HashMap::from([(Animation::Idle, State::new(0.5, true))]),
         vec![],
         Params {
             speed: 0.0,
@@ -36,13 +30,7 @@ fn sm_new() {
 fn sm_new_invalid_starting_state() {
     let err = StateMachine::new(
         Animation::Walk,
-        HashMap::from([(
-            Animation::Idle,
-            State {
-                duration: 0.5,
-                repeat: true,
-            },
-        )]),
+        HashMap::from([(Animation::Idle, State::new(0.5, true))]),
         vec![],
         Params {
             speed: 0.0,
@@ -61,18 +49,12 @@ fn sm_new_invalid_starting_state() {
 fn sm_new_invalid_transition_start_state() {
     let err = StateMachine::new(
         Animation::Idle,
-        HashMap::from([(
-            Animation::Idle,
-            State {
-                duration: 0.5,
-                repeat: true,
-            },
-        )]),
-        vec![Transition {
-            start_state: rsanim::TransitionStartState::Node(Animation::Walk),
-            end_state: rsanim::TransitionEndState::Node(Animation::Idle),
-            trigger: TransitionTrigger::End,
-        }],
+        HashMap::from([(Animation::Idle, State::new(0.5, true))]),
+        vec![Transition::new(
+            rsanim::TransitionStartState::Node(Animation::Walk),
+            rsanim::TransitionEndState::Node(Animation::Idle),
+            TransitionTrigger::End,
+        )],
         Params {
             speed: 0.0,
             jump: false,
@@ -90,18 +72,12 @@ fn sm_new_invalid_transition_start_state() {
 fn sm_new_invalid_transition_end_state() {
     let err = StateMachine::new(
         Animation::Idle,
-        HashMap::from([(
-            Animation::Idle,
-            State {
-                duration: 0.5,
-                repeat: true,
-            },
-        )]),
-        vec![Transition {
-            start_state: rsanim::TransitionStartState::Node(Animation::Idle),
-            end_state: rsanim::TransitionEndState::Node(Animation::Walk),
-            trigger: TransitionTrigger::End,
-        }],
+        HashMap::from([(Animation::Idle, State::new(0.5, true))]),
+        vec![Transition::new(
+            rsanim::TransitionStartState::Node(Animation::Idle),
+            rsanim::TransitionEndState::Node(Animation::Walk),
+            TransitionTrigger::End,
+        )],
         Params {
             speed: 0.0,
             jump: false,
@@ -114,3 +90,29 @@ fn sm_new_invalid_transition_end_state() {
         StateMachineError::InvalidTransitionEndState(Animation::Walk)
     );
 }
+
+#[test]
+fn sm_new_invalid_transition_exit_time() {
+    let err = StateMachine::new(
+        Animation::Idle,
+        HashMap::from([
+            (Animation::Idle, State::new(0.5, true)),
+            (Animation::Walk, State::new(1.0, true)),
+        ]),
+        vec![Transition {
+            exit_time: Some(1.5),
+            ..Transition::new(
+                TransitionStartState::Node(Animation::Idle),
+                TransitionEndState::Node(Animation::Walk),
+                TransitionTrigger::End,
+            )
+        }],
+        Params {
+            speed: 0.0,
+            jump: false,
+        },
+    )
+    .expect_err("invalid transition exit time");
+
+    assert_eq!(err, StateMachineError::InvalidTransitionExitTime(1.5));
+}
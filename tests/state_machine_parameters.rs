@@ -9,13 +9,7 @@ struct Params {
 fn create_sm(starting_state: String, params: Params) -> StateMachine<String, Params> {
     StateMachine::new(
         starting_state,
-        HashMap::from([(
-            "idle".to_string(),
-            State {
-                duration: 0.5,
-                repeat: true,
-            },
-        )]),
+        HashMap::from([("idle".to_string(), State::new(0.5, true))]),
         vec![],
         params,
     )
@@ -0,0 +1,207 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Animation {
+    Ground,
+    Idle,
+    Walk,
+    Death,
+    Missing,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {
+    pub speed: f32,
+}
+
+fn create_sm(speed: f32) -> StateMachine<Animation, Params> {
+    StateMachine::new_nested(
+        Animation::Death,
+        HashMap::from([(Animation::Death, State::new(1.0, false))]),
+        HashMap::from([(
+            Animation::Ground,
+            SubStateMachine {
+                entry_state: Animation::Idle,
+                states: HashMap::from([
+                    (Animation::Idle, State::new(0.5, true)),
+                    (Animation::Walk, State::new(1.0, true)),
+                ]),
+                sub_machines: HashMap::new(),
+                transitions: vec![Transition::new(
+                    TransitionStartState::Node(Animation::Idle),
+                    TransitionEndState::Node(Animation::Walk),
+                    TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed > 0.0)),
+                )],
+            },
+        )]),
+        vec![],
+        Params { speed },
+    )
+    .unwrap()
+}
+
+#[test]
+fn sm_play() {
+    let mut sm = create_sm(0.0);
+
+    let events = sm.play(Animation::Idle).unwrap();
+
+    assert_eq!(sm.state().key, Animation::Idle);
+    assert_eq!(sm.state().parents, vec![Animation::Ground]);
+    assert_eq!(
+        events,
+        vec![
+            StateMachineEvent::Exited(Animation::Death),
+            StateMachineEvent::Entered(Animation::Ground),
+            StateMachineEvent::Entered(Animation::Idle),
+        ]
+    );
+}
+
+#[test]
+fn sm_play_entry_state() {
+    let mut sm = create_sm(0.0);
+
+    sm.play(Animation::Ground).unwrap();
+
+    assert_eq!(sm.state().key, Animation::Idle);
+}
+
+#[test]
+fn sm_play_resolves_transitions() {
+    let mut sm = create_sm(1.0);
+
+    let events = sm.play(Animation::Ground).unwrap();
+
+    assert_eq!(sm.state().key, Animation::Walk);
+    assert_eq!(
+        events,
+        vec![
+            StateMachineEvent::Exited(Animation::Death),
+            StateMachineEvent::Entered(Animation::Ground),
+            StateMachineEvent::Entered(Animation::Idle),
+            StateMachineEvent::Exited(Animation::Idle),
+            StateMachineEvent::TransitionFired {
+                from: Animation::Idle,
+                to: Animation::Walk,
+                index: 0,
+            },
+            StateMachineEvent::Entered(Animation::Walk),
+        ]
+    );
+}
+
+#[test]
+fn sm_play_invalid_state() {
+    let mut sm = create_sm(0.0);
+
+    let error = sm.play(Animation::Missing).expect_err("Expected error");
+
+    assert_eq!(error, StateMachineError::InvalidState(Animation::Missing));
+    assert_eq!(sm.state().key, Animation::Death);
+}
+
+#[test]
+fn sm_play_at() {
+    let mut sm = create_sm(0.0);
+
+    sm.play_at(Animation::Walk, 0.25).unwrap();
+
+    assert_eq!(sm.state().key, Animation::Walk);
+    assert_eq!(sm.state().elapsed, 0.25);
+}
+
+#[test]
+fn sm_play_at_invalid_progress() {
+    let mut sm = create_sm(0.0);
+
+    let error = sm
+        .play_at(Animation::Walk, 1.5)
+        .expect_err("Expected error");
+
+    assert_eq!(error, StateMachineError::InvalidStateProgress(1.5));
+}
+
+#[test]
+fn sm_restart() {
+    let mut sm = create_sm(0.0);
+    sm.update(0.5);
+
+    let events = sm.restart();
+
+    assert_eq!(sm.state().key, Animation::Death);
+    assert_eq!(sm.state().elapsed, 0.0);
+    assert_eq!(
+        events,
+        vec![
+            StateMachineEvent::Exited(Animation::Death),
+            StateMachineEvent::Entered(Animation::Death),
+        ]
+    );
+}
+
+#[test]
+fn sm_restart_nested() {
+    let mut sm = create_sm(0.0);
+    sm.play(Animation::Idle).unwrap();
+    sm.update(1.25);
+
+    let events = sm.restart();
+
+    // the parent state stays active
+    assert_eq!(sm.state().loops, 0);
+    assert_eq!(sm.state().elapsed, 0.0);
+    assert_eq!(
+        events,
+        vec![
+            StateMachineEvent::Exited(Animation::Idle),
+            StateMachineEvent::Entered(Animation::Idle),
+        ]
+    );
+}
+
+#[test]
+fn animator_play() {
+    let mut animator = Animator::new(
+        create_sm(0.0),
+        HashMap::from([
+            (
+                Animation::Death,
+                vec![Frame {
+                    progress: 0.0,
+                    value: 0,
+                }],
+            ),
+            (
+                Animation::Idle,
+                vec![Frame {
+                    progress: 0.0,
+                    value: 1,
+                }],
+            ),
+            (
+                Animation::Walk,
+                vec![
+                    Frame {
+                        progress: 0.0,
+                        value: 2,
+                    },
+                    Frame {
+                        progress: 0.5,
+                        value: 3,
+                    },
+                ],
+            ),
+        ]),
+    )
+    .unwrap();
+
+    animator.play_at(Animation::Walk, 0.75).unwrap();
+    assert_eq!(animator.frame(), &3);
+
+    animator.restart();
+    assert_eq!(animator.frame(), &2);
+
+    animator.play(Animation::Ground).unwrap();
+    assert_eq!(animator.frame(), &1);
+}
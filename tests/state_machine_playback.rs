@@ -0,0 +1,145 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Animation {
+    Idle,
+    Done,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {}
+
+fn create_sm(playback: Playback) -> StateMachine<Animation, Params> {
+    StateMachine::new(
+        Animation::Idle,
+        HashMap::from([
+            (Animation::Idle, State::with_playback(1.0, playback)),
+            (Animation::Done, State::new(1.0, true)),
+        ]),
+        vec![Transition::new(
+            TransitionStartState::Node(Animation::Idle),
+            TransitionEndState::Node(Animation::Done),
+            TransitionTrigger::End,
+        )],
+        Params {},
+    )
+    .unwrap()
+}
+
+#[test]
+fn state_new_repeat() {
+    assert_eq!(State::new(1.0, true).playback, Playback::Loop);
+    assert_eq!(State::new(1.0, false).playback, Playback::Once);
+}
+
+#[test]
+fn sm_playback_loop_count() {
+    let mut sm = create_sm(Playback::LoopCount(3));
+
+    sm.update(1.25);
+    assert_eq!(sm.state().key, Animation::Idle);
+    assert_eq!(sm.state().loops, 1);
+    assert_eq!(sm.state().elapsed, 0.25);
+
+    sm.update(1.0);
+    assert_eq!(sm.state().key, Animation::Idle);
+    assert_eq!(sm.state().loops, 2);
+
+    sm.update(1.0);
+    assert_eq!(sm.state().key, Animation::Done);
+    assert_eq!(sm.state().elapsed, 0.25);
+}
+
+#[test]
+fn sm_playback_loop_count_single_update() {
+    let mut sm = create_sm(Playback::LoopCount(3));
+
+    sm.update(3.5);
+
+    assert_eq!(sm.state().key, Animation::Done);
+    assert_eq!(sm.state().elapsed, 0.5);
+}
+
+#[test]
+fn sm_playback_ping_pong() {
+    let mut sm = create_sm(Playback::PingPong);
+
+    sm.update(0.75);
+    assert_eq!(sm.state().direction, PlaybackDirection::Forward);
+    assert_eq!(sm.state().elapsed, 0.75);
+
+    sm.update(0.5);
+    assert_eq!(sm.state().key, Animation::Idle);
+    assert_eq!(sm.state().direction, PlaybackDirection::Backward);
+    assert_eq!(sm.state().elapsed, 0.75);
+
+    // reaching the start again completes a loop, which triggers the end transition
+    sm.update(1.0);
+    assert_eq!(sm.state().key, Animation::Done);
+    assert_eq!(sm.state().elapsed, 0.25);
+}
+
+#[test]
+fn sm_playback_ping_pong_count() {
+    let mut sm = StateMachine::new(
+        Animation::Idle,
+        HashMap::from([(
+            Animation::Idle,
+            State::with_playback(1.0, Playback::PingPongCount(2)),
+        )]),
+        vec![],
+        Params {},
+    )
+    .unwrap();
+
+    sm.update(2.5);
+    assert_eq!(sm.state().loops, 1);
+    assert_eq!(sm.state().direction, PlaybackDirection::Forward);
+    assert_eq!(sm.state().elapsed, 0.5);
+
+    // holds on the first frame once all loops are played
+    sm.update(5.0);
+    assert_eq!(sm.state().loops, 2);
+    assert_eq!(sm.state().direction, PlaybackDirection::Backward);
+    assert_eq!(sm.state().elapsed, 0.0);
+    assert!(sm.state().finished());
+}
+
+#[test]
+fn sm_playback_clamp() {
+    let mut sm = create_sm(Playback::Clamp);
+
+    sm.update(5.0);
+
+    // holds on the last frame without triggering the end transition
+    assert_eq!(sm.state().key, Animation::Idle);
+    assert_eq!(sm.state().elapsed, 1.0);
+    assert!(sm.state().finished());
+
+    sm.update(1.0);
+    assert_eq!(sm.state().key, Animation::Idle);
+}
+
+#[test]
+fn sm_playback_once() {
+    let mut sm = create_sm(Playback::Once);
+
+    sm.update(1.5);
+
+    assert_eq!(sm.state().key, Animation::Done);
+    assert_eq!(sm.state().elapsed, 0.5);
+}
+
+#[test]
+fn sm_playback_ping_pong_reverse() {
+    let mut sm = create_sm(Playback::PingPong);
+    sm.set_speed(-1.0);
+
+    assert_eq!(sm.state().direction, PlaybackDirection::Backward);
+
+    // the idle was at its start, so it bounces off it straight away
+    sm.update(0.25);
+    assert_eq!(sm.state().key, Animation::Idle);
+    assert_eq!(sm.state().direction, PlaybackDirection::Forward);
+    assert_eq!(sm.state().elapsed, 0.25);
+}
@@ -0,0 +1,112 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Animation {
+    Idle,
+    Walk,
+    Flinch,
+    Wave,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {}
+
+fn create_sm(resume: bool) -> StateMachine<Animation, Params> {
+    StateMachine::new(
+        Animation::Idle,
+        HashMap::from([
+            (Animation::Idle, State::new(1.0, true)),
+            (Animation::Walk, State::new(1.0, true)),
+            (Animation::Flinch, State::new(0.25, false)),
+            (Animation::Wave, State::new(0.5, false)),
+        ]),
+        vec![
+            Transition::new(
+                TransitionStartState::Node(Animation::Idle),
+                TransitionEndState::Node(Animation::Walk),
+                TransitionTrigger::Event("walk".to_string()),
+            ),
+            Transition::new(
+                TransitionStartState::AnyExcept(vec![Animation::Flinch]),
+                TransitionEndState::Node(Animation::Flinch),
+                TransitionTrigger::Event("flinch".to_string()),
+            ),
+            Transition::new(
+                TransitionStartState::Any,
+                TransitionEndState::Node(Animation::Wave),
+                TransitionTrigger::Event("wave".to_string()),
+            ),
+            Transition::new(
+                TransitionStartState::AnyOf(vec![Animation::Flinch, Animation::Wave]),
+                TransitionEndState::Previous { resume },
+                TransitionTrigger::End,
+            ),
+        ],
+        Params {},
+    )
+    .unwrap()
+}
+
+#[test]
+fn sm_previous() {
+    let mut sm = create_sm(false);
+    sm.fire("walk");
+    sm.update(0.5);
+    sm.fire("flinch");
+
+    assert_eq!(sm.history().len(), 1);
+    assert_eq!(sm.history()[0].key, Animation::Walk);
+
+    sm.update(0.375);
+
+    assert_eq!(sm.state().key, Animation::Walk);
+    assert_eq!(sm.state().elapsed, 0.125);
+    assert!(sm.history().is_empty());
+}
+
+#[test]
+fn sm_previous_resume() {
+    let mut sm = create_sm(true);
+    sm.fire("walk");
+    sm.update(0.5);
+    sm.fire("flinch");
+    sm.update(0.375);
+
+    assert_eq!(sm.state().key, Animation::Walk);
+    assert_eq!(sm.state().elapsed, 0.625);
+}
+
+#[test]
+fn sm_previous_without_history() {
+    let mut sm = create_sm(false);
+    sm.play(Animation::Flinch).unwrap();
+    sm.set_history_depth(0);
+
+    assert_eq!(sm.history_depth(), 0);
+    assert!(sm.history().is_empty());
+
+    sm.update(0.5);
+
+    assert_eq!(sm.state().key, Animation::Flinch);
+}
+
+#[test]
+fn sm_previous_history_depth() {
+    let mut sm = create_sm(false);
+    sm.set_history_depth(2);
+    sm.fire("walk");
+    sm.fire("wave");
+    sm.fire("flinch");
+
+    assert_eq!(
+        sm.history().iter().map(|x| &x.key).collect::<Vec<_>>(),
+        vec![&Animation::Walk, &Animation::Wave]
+    );
+
+    // returns to the wave first, then to the walk
+    sm.update(0.25);
+    assert_eq!(sm.state().key, Animation::Wave);
+
+    sm.update(0.5);
+    assert_eq!(sm.state().key, Animation::Walk);
+}
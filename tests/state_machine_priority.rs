@@ -0,0 +1,171 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Animation {
+    Idle,
+    Jump,
+    Crouch,
+    Death,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {}
+
+fn create_sm(transitions: Vec<Transition<Animation, Params>>) -> StateMachine<Animation, Params> {
+    StateMachine::new(
+        Animation::Idle,
+        HashMap::from([
+            (Animation::Idle, State::new(1.0, true)),
+            (Animation::Jump, State::new(1.0, false)),
+            (Animation::Crouch, State::new(1.0, true)),
+            (Animation::Death, State::new(1.0, false)),
+        ]),
+        transitions,
+        Params {},
+    )
+    .unwrap()
+}
+
+fn on_input(
+    start_state: TransitionStartState<Animation>,
+    end_state: Animation,
+) -> Transition<Animation, Params> {
+    Transition::new(
+        start_state,
+        TransitionEndState::Node(end_state),
+        TransitionTrigger::Event("input".to_string()),
+    )
+}
+
+#[test]
+fn sm_priority_declaration_order() {
+    let mut sm = create_sm(vec![
+        on_input(TransitionStartState::Node(Animation::Idle), Animation::Jump),
+        on_input(
+            TransitionStartState::Node(Animation::Idle),
+            Animation::Crouch,
+        ),
+    ]);
+
+    sm.fire("input");
+    assert_eq!(sm.state().key, Animation::Jump);
+}
+
+#[test]
+fn sm_priority_highest() {
+    let mut sm = create_sm(vec![
+        on_input(TransitionStartState::Node(Animation::Idle), Animation::Jump),
+        Transition {
+            priority: 1,
+            ..on_input(TransitionStartState::Any, Animation::Death)
+        },
+        on_input(
+            TransitionStartState::Node(Animation::Idle),
+            Animation::Crouch,
+        ),
+    ]);
+
+    sm.fire("input");
+    assert_eq!(sm.state().key, Animation::Death);
+}
+
+#[test]
+fn sm_priority_specific_before_any() {
+    let mut sm = create_sm(vec![
+        on_input(TransitionStartState::Any, Animation::Death),
+        on_input(TransitionStartState::AnyExcept(vec![]), Animation::Death),
+        on_input(
+            TransitionStartState::Node(Animation::Idle),
+            Animation::Crouch,
+        ),
+    ]);
+
+    sm.fire("input");
+    assert_eq!(sm.state().key, Animation::Crouch);
+}
+
+#[test]
+fn sm_priority_ambiguities_not_reported() {
+    let mut sm = create_sm(vec![
+        on_input(TransitionStartState::Node(Animation::Idle), Animation::Jump),
+        on_input(
+            TransitionStartState::Node(Animation::Idle),
+            Animation::Crouch,
+        ),
+    ]);
+    assert!(!sm.report_ambiguities());
+
+    assert_eq!(
+        sm.fire("input"),
+        vec![
+            StateMachineEvent::Exited(Animation::Idle),
+            StateMachineEvent::TransitionFired {
+                from: Animation::Idle,
+                to: Animation::Jump,
+                index: 0,
+            },
+            StateMachineEvent::Entered(Animation::Jump),
+        ]
+    );
+}
+
+#[test]
+fn sm_priority_ambiguities_reported() {
+    let mut sm = create_sm(vec![
+        on_input(TransitionStartState::Any, Animation::Death),
+        on_input(
+            TransitionStartState::Node(Animation::Jump),
+            Animation::Crouch,
+        ),
+        on_input(TransitionStartState::Node(Animation::Idle), Animation::Jump),
+        Transition {
+            priority: -1,
+            ..on_input(
+                TransitionStartState::Node(Animation::Idle),
+                Animation::Crouch,
+            )
+        },
+    ]);
+    sm.set_report_ambiguities(true);
+
+    assert_eq!(
+        sm.fire("input"),
+        vec![
+            StateMachineEvent::Ambiguous {
+                transitions: vec![2, 0, 3],
+            },
+            StateMachineEvent::Exited(Animation::Idle),
+            StateMachineEvent::TransitionFired {
+                from: Animation::Idle,
+                to: Animation::Jump,
+                index: 2,
+            },
+            StateMachineEvent::Entered(Animation::Jump),
+        ]
+    );
+}
+
+#[test]
+fn sm_priority_single_match_not_ambiguous() {
+    let mut sm = create_sm(vec![
+        on_input(TransitionStartState::Node(Animation::Idle), Animation::Jump),
+        on_input(
+            TransitionStartState::Node(Animation::Jump),
+            Animation::Crouch,
+        ),
+    ]);
+    sm.set_report_ambiguities(true);
+
+    assert_eq!(
+        sm.fire("input"),
+        vec![
+            StateMachineEvent::Exited(Animation::Idle),
+            StateMachineEvent::TransitionFired {
+                from: Animation::Idle,
+                to: Animation::Jump,
+                index: 0,
+            },
+            StateMachineEvent::Entered(Animation::Jump),
+        ]
+    );
+}
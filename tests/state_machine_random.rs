@@ -0,0 +1,188 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Animation {
+    Idle,
+    Idle2,
+    Idle3,
+    Missing,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {}
+
+fn create_sm(
+    targets: Vec<(Animation, u32)>,
+) -> Result<StateMachine<Animation, Params>, StateMachineError<Animation>> {
+    StateMachine::new(
+        Animation::Idle,
+        HashMap::from([
+            (Animation::Idle, State::new(1.0, true)),
+            (Animation::Idle2, State::new(1.0, false)),
+            (Animation::Idle3, State::new(1.0, false)),
+        ]),
+        vec![
+            Transition::new(
+                TransitionStartState::Node(Animation::Idle),
+                TransitionEndState::Random(targets),
+                TransitionTrigger::End,
+            ),
+            Transition::new(
+                TransitionStartState::AnyOf(vec![Animation::Idle2, Animation::Idle3]),
+                TransitionEndState::Node(Animation::Idle),
+                TransitionTrigger::End,
+            ),
+        ],
+        Params {},
+    )
+}
+
+fn idle_loops(sm: &mut StateMachine<Animation, Params>, loops: usize) -> Vec<Animation> {
+    (0..loops)
+        .map(|_| {
+            sm.update(1.0);
+            let key = sm.state().key.clone();
+            if key != Animation::Idle {
+                sm.update(1.0);
+            }
+            key
+        })
+        .collect()
+}
+
+#[test]
+fn sm_random_weights() {
+    let mut sm = create_sm(vec![
+        (Animation::Idle, 60),
+        (Animation::Idle2, 30),
+        (Animation::Idle3, 10),
+    ])
+    .unwrap();
+
+    let picks = idle_loops(&mut sm, 1000);
+    let count = |key| picks.iter().filter(|x| **x == key).count();
+
+    assert!((500..700).contains(&count(Animation::Idle)));
+    assert!((220..380).contains(&count(Animation::Idle2)));
+    assert!((50..150).contains(&count(Animation::Idle3)));
+}
+
+#[test]
+fn sm_random_zero_weight() {
+    let mut sm = create_sm(vec![(Animation::Idle2, 1), (Animation::Idle3, 0)]).unwrap();
+
+    let picks = idle_loops(&mut sm, 100);
+
+    assert!(picks.iter().all(|x| *x == Animation::Idle2));
+}
+
+#[test]
+fn sm_random_deterministic() {
+    let targets = vec![(Animation::Idle2, 1), (Animation::Idle3, 1)];
+
+    let mut sm = create_sm(targets.clone()).unwrap();
+    sm.set_seed(42);
+    let mut other = create_sm(targets).unwrap();
+    other.set_seed(42);
+
+    assert_eq!(idle_loops(&mut sm, 50), idle_loops(&mut other, 50));
+}
+
+#[test]
+fn sm_random_clone() {
+    let mut sm = create_sm(vec![(Animation::Idle2, 1), (Animation::Idle3, 1)]).unwrap();
+    idle_loops(&mut sm, 10);
+
+    // the clone continues with the same random numbers
+    let mut clone = sm.clone();
+
+    assert_eq!(idle_loops(&mut sm, 50), idle_loops(&mut clone, 50));
+}
+
+#[test]
+fn sm_random_seed() {
+    let targets = vec![(Animation::Idle2, 1), (Animation::Idle3, 1)];
+
+    let mut sm = create_sm(targets.clone()).unwrap();
+    sm.set_seed(1);
+    let mut other = create_sm(targets).unwrap();
+    other.set_seed(2);
+
+    assert_ne!(idle_loops(&mut sm, 50), idle_loops(&mut other, 50));
+}
+
+#[test]
+fn sm_random_invalid_end_state() {
+    let error = create_sm(vec![(Animation::Idle2, 1), (Animation::Missing, 1)])
+        .expect_err("Expected error");
+
+    assert_eq!(
+        error,
+        StateMachineError::InvalidTransitionEndState(Animation::Missing)
+    );
+}
+
+#[test]
+fn sm_random_invalid_weights() {
+    let error = create_sm(vec![(Animation::Idle2, 0)]).expect_err("Expected error");
+
+    assert_eq!(error, StateMachineError::InvalidTransitionEndWeights);
+}
+
+fn create_stay_sm() -> StateMachine<Animation, ParameterMap> {
+    let mut transition = Transition::new(
+        TransitionStartState::Node(Animation::Idle),
+        TransitionEndState::Node(Animation::Idle2),
+        TransitionTrigger::All(vec![
+            TransitionTrigger::Event("go".to_string()),
+            TransitionTrigger::Compare(Comparison::new("ready", ComparisonOperator::Equal, true)),
+        ]),
+    );
+    transition.priority = 1;
+
+    StateMachine::new(
+        Animation::Idle,
+        HashMap::from([
+            (Animation::Idle, State::new(1.0, true)),
+            (Animation::Idle2, State::new(1.0, true)),
+        ]),
+        vec![
+            Transition::new(
+                TransitionStartState::Node(Animation::Idle),
+                TransitionEndState::Random(vec![(Animation::Idle, 1)]),
+                TransitionTrigger::Event("go".to_string()),
+            ),
+            Transition::new(
+                TransitionStartState::Node(Animation::Idle),
+                TransitionEndState::Random(vec![(Animation::Idle, 1)]),
+                TransitionTrigger::Compare(Comparison::triggered("jump")),
+            ),
+            transition,
+        ],
+        ParameterMap::new(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn sm_random_stay_keeps_event() {
+    let mut sm = create_stay_sm();
+
+    // picking the active state does not consume the event
+    assert_eq!(sm.fire("go"), vec![]);
+    assert_eq!(sm.state().key, Animation::Idle);
+
+    sm.update_parameters(&|x| x.set("ready", true));
+    assert_eq!(sm.state().key, Animation::Idle2);
+}
+
+#[test]
+fn sm_random_stay_keeps_trigger() {
+    let mut sm = create_stay_sm();
+
+    assert_eq!(sm.update_parameters(&|x| x.trigger("jump")), vec![]);
+    assert_eq!(
+        sm.parameters().get("jump"),
+        Some(ParameterValue::Trigger(true))
+    );
+}
@@ -0,0 +1,132 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Animation {
+    Idle,
+    Hit,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {
+    pub hit: bool,
+}
+
+fn create_sm(self_transition: bool) -> StateMachine<Animation, Params> {
+    StateMachine::new(
+        Animation::Idle,
+        HashMap::from([
+            (Animation::Idle, State::new(1.0, true)),
+            (Animation::Hit, State::new(1.0, false)),
+        ]),
+        vec![
+            Transition {
+                self_transition,
+                ..Transition::new(
+                    TransitionStartState::Any,
+                    TransitionEndState::Node(Animation::Hit),
+                    TransitionTrigger::Condition(Arc::new(|x: &Params| x.hit)),
+                )
+            },
+            Transition {
+                self_transition,
+                ..Transition::new(
+                    TransitionStartState::Node(Animation::Hit),
+                    TransitionEndState::Node(Animation::Hit),
+                    TransitionTrigger::Event("hit".to_string()),
+                )
+            },
+            Transition::new(
+                TransitionStartState::Node(Animation::Hit),
+                TransitionEndState::Node(Animation::Idle),
+                TransitionTrigger::End,
+            ),
+        ],
+        Params { hit: false },
+    )
+    .unwrap()
+}
+
+#[test]
+fn sm_self_transition_disabled() {
+    let mut sm = create_sm(false);
+    sm.fire("hit");
+    sm.update_parameters(&|x| x.hit = true);
+    sm.update(0.5);
+
+    sm.fire("hit");
+
+    assert_eq!(sm.state().key, Animation::Hit);
+    assert_eq!(sm.state().elapsed, 0.5);
+}
+
+#[test]
+fn sm_self_transition_event() {
+    let mut sm = create_sm(true);
+    sm.update_parameters(&|x| x.hit = true);
+    sm.update_parameters(&|x| x.hit = false);
+    sm.update(0.5);
+
+    let events = sm.fire("hit");
+
+    assert_eq!(sm.state().key, Animation::Hit);
+    assert_eq!(sm.state().elapsed, 0.0);
+    assert_eq!(
+        events,
+        vec![
+            StateMachineEvent::Exited(Animation::Hit),
+            StateMachineEvent::TransitionFired {
+                from: Animation::Hit,
+                to: Animation::Hit,
+                index: 1,
+            },
+            StateMachineEvent::Entered(Animation::Hit),
+        ]
+    );
+}
+
+#[test]
+fn sm_self_transition_condition_enters_once() {
+    let mut sm = create_sm(true);
+
+    let events = sm.update_parameters(&|x| x.hit = true);
+
+    assert_eq!(
+        events,
+        vec![
+            StateMachineEvent::Exited(Animation::Idle),
+            StateMachineEvent::TransitionFired {
+                from: Animation::Idle,
+                to: Animation::Hit,
+                index: 0,
+            },
+            StateMachineEvent::Entered(Animation::Hit),
+        ]
+    );
+}
+
+#[test]
+fn sm_self_transition_condition_latched() {
+    let mut sm = create_sm(true);
+    sm.update_parameters(&|x| x.hit = true);
+    sm.update(0.5);
+
+    // the condition is still true, but it has not been false since the hit
+    sm.update_parameters(&|x| x.hit = true);
+    sm.update(0.25);
+
+    assert_eq!(sm.state().key, Animation::Hit);
+    assert_eq!(sm.state().elapsed, 0.75);
+}
+
+#[test]
+fn sm_self_transition_condition_released() {
+    let mut sm = create_sm(true);
+    sm.update_parameters(&|x| x.hit = true);
+    sm.update(0.5);
+
+    sm.update_parameters(&|x| x.hit = false);
+    sm.update_parameters(&|x| x.hit = true);
+
+    assert_eq!(sm.state().key, Animation::Hit);
+    assert_eq!(sm.state().elapsed, 0.0);
+}
@@ -0,0 +1,124 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Animation {
+    Idle,
+    Turn,
+    Walk,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {}
+
+fn create_sm(starting_state: Animation) -> StateMachine<Animation, Params> {
+    StateMachine::new(
+        starting_state,
+        HashMap::from([
+            (Animation::Idle, State::new(1.0, true)),
+            (
+                Animation::Turn,
+                State {
+                    speed: -2.0,
+                    ..State::new(1.0, false)
+                },
+            ),
+            (Animation::Walk, State::new(1.0, true)),
+        ]),
+        vec![Transition::new(
+            TransitionStartState::Node(Animation::Turn),
+            TransitionEndState::Node(Animation::Walk),
+            TransitionTrigger::End,
+        )],
+        Params {},
+    )
+    .unwrap()
+}
+
+#[test]
+fn sm_speed_default() {
+    let sm = create_sm(Animation::Idle);
+
+    assert_eq!(sm.speed(), 1.0);
+    assert_eq!(sm.state().speed, 1.0);
+}
+
+#[test]
+fn sm_set_speed() {
+    let mut sm = create_sm(Animation::Idle);
+
+    sm.set_speed(0.5);
+    sm.update(0.5);
+
+    assert_eq!(sm.speed(), 0.5);
+    assert_eq!(sm.state().speed, 0.5);
+    assert_eq!(sm.state().elapsed, 0.25);
+}
+
+#[test]
+fn sm_set_speed_pause() {
+    let mut sm = create_sm(Animation::Idle);
+
+    sm.set_speed(0.0);
+    sm.update(0.5);
+
+    assert_eq!(sm.state().elapsed, 0.0);
+}
+
+#[test]
+fn sm_reverse_starts_at_end() {
+    let sm = create_sm(Animation::Turn);
+
+    assert_eq!(sm.state().speed, -2.0);
+    assert_eq!(sm.state().elapsed, 1.0);
+    assert_eq!(sm.state().progress(), 1.0);
+    assert!(!sm.state().finished());
+}
+
+#[test]
+fn sm_reverse_update() {
+    let mut sm = create_sm(Animation::Turn);
+
+    sm.update(0.125);
+
+    assert_eq!(sm.state().key, Animation::Turn);
+    assert_eq!(sm.state().elapsed, 0.75);
+}
+
+#[test]
+fn sm_reverse_end_transition() {
+    let mut sm = create_sm(Animation::Turn);
+
+    sm.update(0.75);
+
+    // the turn reaches its start after 0.5, the rest is carried into the walk
+    assert_eq!(sm.state().key, Animation::Walk);
+    assert_eq!(sm.state().elapsed, 0.25);
+}
+
+#[test]
+fn sm_reverse_repeat() {
+    let mut sm = create_sm(Animation::Idle);
+
+    sm.set_speed(-1.0);
+    sm.update(0.25);
+
+    // the idle was at its start, so it wraps around to its end
+    assert_eq!(sm.state().elapsed, 0.75);
+
+    sm.update(1.5);
+
+    assert_eq!(sm.state().elapsed, 0.25);
+}
+
+#[test]
+fn sm_global_reverse_turns_forward() {
+    let mut sm = create_sm(Animation::Turn);
+
+    sm.set_speed(-1.0);
+
+    assert_eq!(sm.state().speed, 2.0);
+
+    sm.update(0.5);
+
+    assert_eq!(sm.state().key, Animation::Walk);
+}
@@ -0,0 +1,122 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Animation {
+    Idle,
+    Walk,
+    Fall,
+    Death,
+    Missing,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {}
+
+fn grounded(duration: f32) -> State {
+    State {
+        tags: vec!["grounded".to_string()],
+        ..State::new(duration, true)
+    }
+}
+
+fn create_sm(
+    starting_state: Animation,
+    start_state: TransitionStartState<Animation>,
+) -> Result<StateMachine<Animation, Params>, StateMachineError<Animation>> {
+    StateMachine::new(
+        starting_state,
+        HashMap::from([
+            (Animation::Idle, grounded(0.5)),
+            (Animation::Walk, grounded(1.0)),
+            (Animation::Fall, State::new(1.0, true)),
+            (Animation::Death, State::new(1.0, false)),
+        ]),
+        vec![Transition::new(
+            start_state,
+            TransitionEndState::Node(Animation::Death),
+            TransitionTrigger::Event("die".to_string()),
+        )],
+        Params {},
+    )
+}
+
+#[test]
+fn sm_start_state_any_of() {
+    let start_state = TransitionStartState::AnyOf(vec![Animation::Idle, Animation::Walk]);
+
+    let mut sm = create_sm(Animation::Walk, start_state.clone()).unwrap();
+    sm.fire("die");
+    assert_eq!(sm.state().key, Animation::Death);
+
+    let mut sm = create_sm(Animation::Fall, start_state).unwrap();
+    sm.fire("die");
+    assert_eq!(sm.state().key, Animation::Fall);
+}
+
+#[test]
+fn sm_start_state_any_except() {
+    let start_state = TransitionStartState::AnyExcept(vec![Animation::Fall]);
+
+    let mut sm = create_sm(Animation::Idle, start_state.clone()).unwrap();
+    sm.fire("die");
+    assert_eq!(sm.state().key, Animation::Death);
+
+    let mut sm = create_sm(Animation::Fall, start_state).unwrap();
+    sm.fire("die");
+    assert_eq!(sm.state().key, Animation::Fall);
+}
+
+#[test]
+fn sm_start_state_tag() {
+    let start_state = TransitionStartState::Tag("grounded".to_string());
+
+    let mut sm = create_sm(Animation::Idle, start_state.clone()).unwrap();
+    sm.fire("die");
+    assert_eq!(sm.state().key, Animation::Death);
+
+    let mut sm = create_sm(Animation::Fall, start_state).unwrap();
+    sm.fire("die");
+    assert_eq!(sm.state().key, Animation::Fall);
+}
+
+#[test]
+fn sm_start_state_any_of_invalid() {
+    let error = create_sm(
+        Animation::Idle,
+        TransitionStartState::AnyOf(vec![Animation::Idle, Animation::Missing]),
+    )
+    .expect_err("Expected error");
+
+    assert_eq!(
+        error,
+        StateMachineError::InvalidTransitionStartState(Animation::Missing)
+    );
+}
+
+#[test]
+fn sm_start_state_any_except_invalid() {
+    let error = create_sm(
+        Animation::Idle,
+        TransitionStartState::AnyExcept(vec![Animation::Missing]),
+    )
+    .expect_err("Expected error");
+
+    assert_eq!(
+        error,
+        StateMachineError::InvalidTransitionStartState(Animation::Missing)
+    );
+}
+
+#[test]
+fn sm_start_state_tag_invalid() {
+    let error = create_sm(
+        Animation::Idle,
+        TransitionStartState::Tag("airborne".to_string()),
+    )
+    .expect_err("Expected error");
+
+    assert_eq!(
+        error,
+        StateMachineError::InvalidTransitionStartTag("airborne".to_string())
+    );
+}
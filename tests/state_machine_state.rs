@@ -14,13 +14,7 @@ struct Params {
 fn create_sm(starting_state: Animation, params: Params) -> StateMachine<Animation, Params> {
     StateMachine::new(
         starting_state,
-        HashMap::from([(
-            Animation::Idle,
-            State {
-                duration: 0.5,
-                repeat: true,
-            },
-        )]),
+        HashMap::from([(Animation::Idle, State::new(0.5, true))]),
         vec![],
         params,
     )
@@ -43,7 +37,11 @@ fn sm_state() {
             key: Animation::Idle,
             elapsed: 0.0,
             duration: 0.5,
-            repeat: true,
+            playback: Playback::Loop,
+            speed: 1.0,
+            loops: 0,
+            direction: PlaybackDirection::Forward,
+            parents: vec![],
         }
     );
 }
@@ -66,7 +64,11 @@ fn sm_update_state() {
             key: Animation::Idle,
             elapsed: 0.25,
             duration: 0.5,
-            repeat: true,
+            playback: Playback::Loop,
+            speed: 1.0,
+            loops: 0,
+            direction: PlaybackDirection::Forward,
+            parents: vec![],
         }
     );
 }
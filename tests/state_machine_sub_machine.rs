@@ -0,0 +1,292 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Animation {
+    Locomotion,
+    Idle,
+    Walk,
+    Combat,
+    Attack,
+    Block,
+    Death,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {
+    pub speed: f32,
+    pub block: bool,
+    pub dead: bool,
+}
+
+fn params() -> Params {
+    Params {
+        speed: 0.0,
+        block: false,
+        dead: false,
+    }
+}
+
+fn locomotion() -> SubStateMachine<Animation, Params> {
+    SubStateMachine {
+        entry_state: Animation::Idle,
+        states: HashMap::from([
+            (Animation::Idle, State::new(0.5, true)),
+            (Animation::Walk, State::new(1.0, true)),
+        ]),
+        sub_machines: HashMap::new(),
+        transitions: vec![
+            Transition::new(
+                TransitionStartState::Node(Animation::Idle),
+                TransitionEndState::Node(Animation::Walk),
+                TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed > 0.0)),
+            ),
+            Transition::new(
+                TransitionStartState::Node(Animation::Walk),
+                TransitionEndState::Node(Animation::Idle),
+                TransitionTrigger::Condition(Arc::new(|x: &Params| x.speed <= 0.0)),
+            ),
+        ],
+    }
+}
+
+fn combat() -> SubStateMachine<Animation, Params> {
+    SubStateMachine {
+        entry_state: Animation::Attack,
+        states: HashMap::from([
+            (Animation::Attack, State::new(0.5, false)),
+            (Animation::Block, State::new(0.5, true)),
+        ]),
+        sub_machines: HashMap::new(),
+        transitions: vec![
+            Transition::new(
+                TransitionStartState::Any,
+                TransitionEndState::Node(Animation::Block),
+                TransitionTrigger::Condition(Arc::new(|x: &Params| x.block)),
+            ),
+            Transition::new(
+                TransitionStartState::Node(Animation::Attack),
+                TransitionEndState::Node(Animation::Block),
+                TransitionTrigger::Event("interrupt".to_string()),
+            ),
+            Transition::new(
+                TransitionStartState::Node(Animation::Block),
+                TransitionEndState::Node(Animation::Locomotion),
+                TransitionTrigger::Condition(Arc::new(|x: &Params| !x.block)),
+            ),
+        ],
+    }
+}
+
+fn create_sm(starting_state: Animation, params: Params) -> StateMachine<Animation, Params> {
+    StateMachine::new_nested(
+        starting_state,
+        HashMap::from([(Animation::Death, State::new(1.0, false))]),
+        HashMap::from([
+            (Animation::Locomotion, locomotion()),
+            (Animation::Combat, combat()),
+        ]),
+        vec![
+            Transition::new(
+                TransitionStartState::Node(Animation::Locomotion),
+                TransitionEndState::Node(Animation::Combat),
+                TransitionTrigger::Event("attack".to_string()),
+            ),
+            Transition::new(
+                TransitionStartState::Node(Animation::Combat),
+                TransitionEndState::Node(Animation::Locomotion),
+                TransitionTrigger::Event("interrupt".to_string()),
+            ),
+            Transition::new(
+                TransitionStartState::Any,
+                TransitionEndState::Node(Animation::Death),
+                TransitionTrigger::Condition(Arc::new(|x: &Params| x.dead)),
+            ),
+        ],
+        params,
+    )
+    .unwrap()
+}
+
+#[test]
+fn sub_machine_entry_state() {
+    let sm = create_sm(Animation::Locomotion, params());
+
+    assert_eq!(
+        sm.state(),
+        &CurrentState {
+            key: Animation::Idle,
+            duration: 0.5,
+            elapsed: 0.0,
+            playback: Playback::Loop,
+            speed: 1.0,
+            loops: 0,
+            direction: PlaybackDirection::Forward,
+            parents: vec![Animation::Locomotion],
+        }
+    );
+    assert_eq!(
+        sm.state().path().collect::<Vec<_>>(),
+        vec![&Animation::Locomotion, &Animation::Idle]
+    );
+}
+
+#[test]
+fn sub_machine_local_transition() {
+    let mut sm = create_sm(Animation::Locomotion, params());
+
+    sm.update_parameters(&|x| {
+        x.speed = 1.0;
+    });
+
+    assert_eq!(sm.state().key, Animation::Walk);
+    assert_eq!(sm.state().parents, vec![Animation::Locomotion]);
+}
+
+#[test]
+fn sub_machine_parent_transition() {
+    let mut sm = create_sm(Animation::Walk, params());
+
+    sm.fire("attack");
+
+    assert_eq!(sm.state().key, Animation::Attack);
+    assert_eq!(sm.state().parents, vec![Animation::Combat]);
+}
+
+#[test]
+fn sub_machine_any_is_scoped() {
+    let mut sm = create_sm(Animation::Locomotion, params());
+
+    sm.update_parameters(&|x| {
+        x.block = true;
+    });
+    assert_eq!(sm.state().key, Animation::Idle);
+
+    sm.fire("attack");
+    assert_eq!(sm.state().key, Animation::Block);
+}
+
+#[test]
+fn sub_machine_root_any() {
+    let mut sm = create_sm(Animation::Attack, params());
+
+    sm.update_parameters(&|x| {
+        x.dead = true;
+    });
+
+    assert_eq!(sm.state().key, Animation::Death);
+    assert_eq!(sm.state().parents, vec![]);
+}
+
+#[test]
+fn sub_machine_exit_transition() {
+    let mut sm = create_sm(Animation::Attack, params());
+
+    sm.update_parameters(&|x| {
+        x.block = true;
+    });
+    assert_eq!(sm.state().key, Animation::Block);
+
+    sm.update_parameters(&|x| {
+        x.block = false;
+    });
+    assert_eq!(sm.state().key, Animation::Idle);
+    assert_eq!(sm.state().parents, vec![Animation::Locomotion]);
+}
+
+#[test]
+fn sub_machine_local_transitions_first() {
+    let mut sm = create_sm(
+        Animation::Attack,
+        Params {
+            block: true,
+            ..params()
+        },
+    );
+
+    // both the local transition to block and the parent transition to locomotion are triggered
+    sm.fire("interrupt");
+
+    assert_eq!(sm.state().key, Animation::Block);
+}
+
+#[test]
+fn sub_machine_duplicate_state() {
+    let err = StateMachine::new_nested(
+        Animation::Locomotion,
+        HashMap::from([(Animation::Idle, State::new(1.0, false))]),
+        HashMap::from([(Animation::Locomotion, locomotion())]),
+        vec![],
+        params(),
+    )
+    .expect_err("duplicate state");
+
+    assert_eq!(err, StateMachineError::DuplicateState(Animation::Idle));
+}
+
+#[test]
+fn sub_machine_invalid_entry_state() {
+    let err = StateMachine::new_nested(
+        Animation::Locomotion,
+        HashMap::new(),
+        HashMap::from([(
+            Animation::Locomotion,
+            SubStateMachine {
+                entry_state: Animation::Attack,
+                ..locomotion()
+            },
+        )]),
+        vec![],
+        params(),
+    )
+    .expect_err("invalid entry state");
+
+    assert_eq!(err, StateMachineError::InvalidEntryState(Animation::Attack));
+}
+
+#[test]
+fn sub_machine_invalid_transition_start_state() {
+    let err = StateMachine::new_nested(
+        Animation::Locomotion,
+        HashMap::new(),
+        HashMap::from([(Animation::Locomotion, locomotion())]),
+        vec![Transition::new(
+            TransitionStartState::Node(Animation::Idle),
+            TransitionEndState::Node(Animation::Locomotion),
+            TransitionTrigger::End,
+        )],
+        params(),
+    )
+    .expect_err("invalid transition start state");
+
+    assert_eq!(
+        err,
+        StateMachineError::InvalidTransitionStartState(Animation::Idle)
+    );
+}
+
+#[test]
+fn sub_machine_animator() {
+    let frames = |value: u8| {
+        vec![Frame {
+            progress: 0.0,
+            value,
+        }]
+    };
+    let mut animator = Animator::new(
+        create_sm(Animation::Locomotion, params()),
+        HashMap::from([
+            (Animation::Idle, frames(0)),
+            (Animation::Walk, frames(1)),
+            (Animation::Attack, frames(2)),
+            (Animation::Block, frames(3)),
+            (Animation::Death, frames(4)),
+        ]),
+    )
+    .unwrap();
+
+    animator.update_parameters(&|x| {
+        x.speed = 1.0;
+    });
+
+    assert_eq!(animator.frame(), &1);
+}
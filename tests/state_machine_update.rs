@@ -0,0 +1,127 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Animation {
+    Land,
+    Recover,
+    Idle,
+    Fidget,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {}
+
+fn create_sm(starting_state: Animation) -> StateMachine<Animation, Params> {
+    StateMachine::new(
+        starting_state,
+        HashMap::from([
+            (Animation::Land, State::new(0.25, false)),
+            (Animation::Recover, State::new(0.25, false)),
+            (Animation::Idle, State::new(0.5, true)),
+            (Animation::Fidget, State::new(0.25, false)),
+        ]),
+        vec![
+            Transition::new(
+                TransitionStartState::Node(Animation::Land),
+                TransitionEndState::Node(Animation::Recover),
+                TransitionTrigger::End,
+            ),
+            Transition::new(
+                TransitionStartState::Node(Animation::Recover),
+                TransitionEndState::Node(Animation::Idle),
+                TransitionTrigger::End,
+            ),
+            Transition::new(
+                TransitionStartState::Node(Animation::Idle),
+                TransitionEndState::Node(Animation::Fidget),
+                TransitionTrigger::End,
+            ),
+        ],
+        Params {},
+    )
+    .unwrap()
+}
+
+#[test]
+fn sm_update_carries_overflow() {
+    let mut sm = create_sm(Animation::Land);
+
+    sm.update(0.375);
+
+    assert_eq!(sm.state().key, Animation::Recover);
+    assert_eq!(sm.state().elapsed, 0.125);
+}
+
+#[test]
+fn sm_update_chains_end_transitions() {
+    let mut sm = create_sm(Animation::Land);
+
+    sm.update(0.625);
+
+    assert_eq!(sm.state().key, Animation::Idle);
+    assert_eq!(sm.state().elapsed, 0.125);
+}
+
+#[test]
+fn sm_update_chains_into_repeating_state() {
+    let mut sm = create_sm(Animation::Land);
+
+    sm.update(1.125);
+
+    assert_eq!(sm.state().key, Animation::Fidget);
+    assert_eq!(sm.state().elapsed, 0.125);
+}
+
+#[test]
+fn sm_update_exact_end() {
+    let mut sm = create_sm(Animation::Land);
+
+    sm.update(0.25);
+
+    assert_eq!(sm.state().key, Animation::Recover);
+    assert_eq!(sm.state().elapsed, 0.0);
+}
+
+#[test]
+fn sm_update_repeat_wraps_several_times() {
+    let mut sm = StateMachine::new(
+        Animation::Idle,
+        HashMap::from([(Animation::Idle, State::new(0.5, true))]),
+        vec![],
+        Params {},
+    )
+    .unwrap();
+
+    sm.update(1.625);
+
+    assert_eq!(sm.state().key, Animation::Idle);
+    assert_eq!(sm.state().elapsed, 0.125);
+}
+
+#[test]
+fn sm_update_zero_duration_cycle() {
+    let mut sm = StateMachine::new(
+        Animation::Land,
+        HashMap::from([
+            (Animation::Land, State::new(0.0, false)),
+            (Animation::Recover, State::new(0.0, false)),
+        ]),
+        vec![
+            Transition::new(
+                TransitionStartState::Node(Animation::Land),
+                TransitionEndState::Node(Animation::Recover),
+                TransitionTrigger::End,
+            ),
+            Transition::new(
+                TransitionStartState::Node(Animation::Recover),
+                TransitionEndState::Node(Animation::Land),
+                TransitionTrigger::End,
+            ),
+        ],
+        Params {},
+    )
+    .unwrap();
+
+    // must not loop forever
+    sm.update(1.0);
+}
@@ -2,11 +2,11 @@ use rsanim::prelude::*;
 
 #[test]
 fn clone() {
-    let transition = Transition {
-        start_state: TransitionStartState::Any,
-        end_state: TransitionEndState::Node("test".to_string()),
-        trigger: TransitionTrigger::<String>::End,
-    };
+    let transition = Transition::new(
+        TransitionStartState::Any,
+        TransitionEndState::Node("test".to_string()),
+        TransitionTrigger::<_, String>::End,
+    );
 
     assert_eq!(
         format!("{:?}", transition.clone()),
@@ -16,14 +16,14 @@ fn clone() {
 
 #[test]
 fn debug() {
-    let transition = Transition {
-        start_state: TransitionStartState::Any,
-        end_state: TransitionEndState::Node("test".to_string()),
-        trigger: TransitionTrigger::<String>::End,
-    };
+    let transition = Transition::new(
+        TransitionStartState::Any,
+        TransitionEndState::Node("test".to_string()),
+        TransitionTrigger::<_, String>::End,
+    );
 
     assert_eq!(
         format!("{:?}", transition),
-        "Transition { start_state: Any, end_state: Node(\"test\"), trigger: End }"
+        "Transition { start_state: Any, end_state: Node(\"test\"), trigger: End, exit_time: None, blend_duration: None, self_transition: false, priority: 0 }"
     );
 }
@@ -2,13 +2,13 @@ use rsanim::prelude::*;
 
 #[test]
 fn clone() {
-    let trigger = TransitionTrigger::<String>::End;
+    let trigger = TransitionTrigger::<(), String>::End;
     assert!(matches!(trigger.clone(), TransitionTrigger::End));
 }
 
 #[test]
 fn debug_end() {
-    assert_eq!(format!("{:?}", TransitionTrigger::<String>::End), "End");
+    assert_eq!(format!("{:?}", TransitionTrigger::<(), String>::End), "End");
 }
 
 #[test]
@@ -16,8 +16,44 @@ fn debug_condition() {
     assert_eq!(
         format!(
             "{:?}",
-            TransitionTrigger::<String>::Condition(Box::new(|_| true))
+            TransitionTrigger::<(), String>::Condition(Arc::new(|_: &String| true))
         ),
         "Condition"
     );
 }
+
+#[test]
+fn debug_event() {
+    assert_eq!(
+        format!(
+            "{:?}",
+            TransitionTrigger::<(), String>::Event("attack".to_string())
+        ),
+        "Event(\"attack\")"
+    );
+}
+
+#[test]
+fn condition_captures() {
+    let threshold = 0.5;
+    let trigger = TransitionTrigger::<(), f32>::Condition(Arc::new(move |x: &f32| *x > threshold));
+
+    match trigger.clone() {
+        TransitionTrigger::Condition(condition) => {
+            assert!(condition(&1.0));
+            assert!(!condition(&0.0));
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn debug_context() {
+    assert_eq!(
+        format!(
+            "{:?}",
+            TransitionTrigger::<(), String>::Context(Arc::new(|_| true))
+        ),
+        "Context"
+    );
+}
@@ -0,0 +1,205 @@
+use rsanim::prelude::*;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Animation {
+    Idle,
+    Attack,
+    Combo,
+    Fidget,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {
+    held: bool,
+}
+
+fn create_sm(
+    starting_state: Animation,
+    trigger: TransitionTrigger<Animation, Params>,
+) -> StateMachine<Animation, Params> {
+    StateMachine::new(
+        starting_state.clone(),
+        HashMap::from([
+            (Animation::Idle, State::new(0.5, true)),
+            (Animation::Attack, State::new(0.5, false)),
+            (Animation::Combo, State::new(0.5, false)),
+            (Animation::Fidget, State::new(1.0, false)),
+        ]),
+        vec![Transition::new(
+            TransitionStartState::Node(starting_state),
+            TransitionEndState::Node(Animation::Combo),
+            trigger,
+        )],
+        Params { held: false },
+    )
+    .unwrap()
+}
+
+fn held() -> TransitionTrigger<Animation, Params> {
+    TransitionTrigger::Condition(Arc::new(|x: &Params| x.held))
+}
+
+#[test]
+fn trigger_all() {
+    let trigger = TransitionTrigger::All(vec![TransitionTrigger::End, held()]);
+
+    let mut sm = create_sm(Animation::Attack, trigger.clone());
+    sm.update(0.6);
+    assert_eq!(sm.state().key, Animation::Attack);
+    sm.update_parameters(&|x| x.held = true);
+    assert_eq!(sm.state().key, Animation::Attack);
+    sm.update(0.1);
+    assert_eq!(sm.state().key, Animation::Combo);
+
+    let mut sm = create_sm(Animation::Attack, trigger);
+    sm.update_parameters(&|x| x.held = true);
+    sm.update(0.4);
+    assert_eq!(sm.state().key, Animation::Attack);
+    sm.update(0.2);
+    assert_eq!(sm.state().key, Animation::Combo);
+}
+
+#[test]
+fn trigger_any() {
+    let trigger =
+        TransitionTrigger::Any(vec![TransitionTrigger::Event("attack".to_string()), held()]);
+
+    let mut sm = create_sm(Animation::Idle, trigger.clone());
+    sm.fire("attack");
+    assert_eq!(sm.state().key, Animation::Combo);
+
+    let mut sm = create_sm(Animation::Idle, trigger);
+    sm.update_parameters(&|x| x.held = true);
+    assert_eq!(sm.state().key, Animation::Combo);
+}
+
+#[test]
+fn trigger_not() {
+    let mut sm = create_sm(Animation::Idle, TransitionTrigger::Not(Box::new(held())));
+    sm.update_parameters(&|x| x.held = true);
+    sm.update(0.1);
+    assert_eq!(sm.state().key, Animation::Idle);
+
+    sm.update_parameters(&|x| x.held = false);
+    assert_eq!(sm.state().key, Animation::Combo);
+}
+
+#[test]
+fn trigger_event_consumed() {
+    let mut sm = create_sm(
+        Animation::Idle,
+        TransitionTrigger::All(vec![
+            TransitionTrigger::Event("attack".to_string()),
+            TransitionTrigger::Not(Box::new(held())),
+        ]),
+    );
+    sm.set_event_lifetime(10);
+
+    sm.fire("attack");
+    assert_eq!(sm.state().key, Animation::Combo);
+
+    sm.play(Animation::Idle).unwrap();
+    assert_eq!(sm.state().key, Animation::Idle);
+}
+
+#[test]
+fn trigger_after() {
+    let mut sm = create_sm(Animation::Idle, TransitionTrigger::After(0.75));
+
+    sm.update(0.5);
+    assert_eq!(sm.state().key, Animation::Idle);
+
+    sm.update(0.5);
+    assert_eq!(sm.state().key, Animation::Combo);
+}
+
+#[test]
+fn trigger_loops() {
+    let mut sm = create_sm(Animation::Idle, TransitionTrigger::Loops(2));
+
+    sm.update(0.75);
+    assert_eq!(sm.state().key, Animation::Idle);
+
+    sm.update(0.5);
+    assert_eq!(sm.state().key, Animation::Combo);
+}
+
+fn create_animator(
+    trigger: TransitionTrigger<Animation, Params>,
+) -> Animator<Animation, Params, u8> {
+    let frames = |count: u8| {
+        (0..count)
+            .map(|x| Frame {
+                progress: f32::from(x) / f32::from(count),
+                value: x,
+            })
+            .collect::<Vec<_>>()
+    };
+    Animator::new(
+        create_sm(Animation::Fidget, trigger),
+        HashMap::from([
+            (Animation::Idle, frames(1)),
+            (Animation::Attack, frames(1)),
+            (Animation::Combo, frames(1)),
+            (Animation::Fidget, frames(4)),
+        ]),
+    )
+    .unwrap()
+}
+
+#[test]
+fn trigger_frame_reached() {
+    let mut animator = create_animator(TransitionTrigger::FrameReached(2));
+
+    animator.update(0.4);
+    assert_eq!(animator.state().key, Animation::Fidget);
+
+    animator.update(0.1);
+    assert_eq!(animator.state().key, Animation::Combo);
+}
+
+#[test]
+fn trigger_frame_reached_backward() {
+    let mut animator = create_animator(TransitionTrigger::FrameReached(1));
+    animator.set_speed(-1.0);
+    animator.restart();
+
+    animator.update(0.4);
+    assert_eq!(animator.state().key, Animation::Fidget);
+
+    animator.update(0.15);
+    assert_eq!(animator.state().key, Animation::Combo);
+}
+
+#[test]
+fn trigger_frame_reached_missing_frame() {
+    let mut animator = create_animator(TransitionTrigger::FrameReached(4));
+
+    animator.update(0.9);
+    assert_eq!(animator.state().key, Animation::Fidget);
+}
+
+#[test]
+fn trigger_frame_reached_state_machine() {
+    let mut sm = create_sm(Animation::Fidget, TransitionTrigger::FrameReached(0));
+
+    sm.update(0.5);
+    assert_eq!(sm.state().key, Animation::Fidget);
+}
+
+#[test]
+fn debug_combinators() {
+    assert_eq!(
+        format!(
+            "{:?}",
+            TransitionTrigger::<(), String>::All(vec![
+                TransitionTrigger::Any(vec![
+                    TransitionTrigger::Loops(2),
+                    TransitionTrigger::FrameReached(3),
+                ]),
+                TransitionTrigger::Not(Box::new(TransitionTrigger::After(0.5))),
+            ])
+        ),
+        "All([Any([Loops(2), FrameReached(3)]), Not(After(0.5))])"
+    );
+}